@@ -1,29 +1,95 @@
+#[cfg(feature = "bundled")]
 use std::env;
+use std::fs;
+#[cfg(feature = "bundled")]
 use std::path::PathBuf;
 
 fn main() {
-    // Build libversion static library using cc.
-    // The cmake-generated headers (config.h, export.h) are pre-committed under
-    // generated/ — run `bash scripts/generate-headers.sh` to regenerate them
-    // whenever the libversion submodule is updated.
-    cc::Build::new()
-        .file("libversion/libversion/compare.c")
-        .file("libversion/libversion/private/compare.c")
-        .file("libversion/libversion/private/parse.c")
-        .include("libversion") // source headers (libversion/version.h, etc.)
-        .include("generated") // pre-generated headers (libversion/config.h, libversion/export.h)
-        .define("LIBVERSION_STATIC_DEFINE", None)
-        .compile("version");
-
-    // Generate FFI bindings via bindgen
-    let bindings = bindgen::Builder::default()
-        .header("wrapper.h")
-        .clang_arg("-Igenerated") // pre-generated config.h, export.h
-        .clang_arg("-Ilibversion") // source headers
-        .clang_arg("-DLIBVERSION_STATIC_DEFINE")
+    let bundled_version = bundled_version();
+
+    // Without the C backend the pure-Rust port is used, which needs neither
+    // the C library nor its bindings. It follows the bundled libversion, so
+    // report that version.
+    #[cfg(not(feature = "bundled"))]
+    set_version_env(&bundled_version);
+    #[cfg(feature = "bundled")]
+    link_libversion(&bundled_version);
+}
+
+/// Link a system libversion if requested and found, or build the bundled
+/// copy, and export its version and headers.
+#[cfg(feature = "bundled")]
+fn link_libversion(bundled_version: &str) {
+    let system = if use_system() {
+        system_library(bundled_version)
+    } else {
+        None
+    };
+
+    // Metadata for dependent build scripts, available to them as
+    // DEP_VERSION_VERSION, DEP_VERSION_INCLUDE and DEP_VERSION_ROOT through
+    // `links = "version"`.
+    match &system {
+        Some(library) => {
+            set_version_env(&library.version);
+            println!("cargo:version={}", library.version);
+            // Headers in a default search path, such as /usr/include, have
+            // no include path of their own.
+            if !library.include_paths.is_empty() {
+                let include = env::join_paths(&library.include_paths)
+                    .expect("pkg-config include paths can't be joined");
+                println!("cargo:include={}", include.to_string_lossy());
+            }
+            if let Ok(prefix) = pkg_config::get_variable("libversion", "prefix") {
+                println!("cargo:root={prefix}");
+            }
+        }
+        None => {
+            set_version_env(bundled_version);
+            build_bundled();
+            let root = install_headers();
+            println!("cargo:version={bundled_version}");
+            println!("cargo:include={}", root.join("include").display());
+            println!("cargo:root={}", root.display());
+        }
+    }
+
+    // Bindings are pre-generated in generated/bindings.rs; the `bindgen`
+    // feature regenerates them from the headers instead.
+    #[cfg(feature = "bindgen")]
+    generate_bindings(system.as_ref());
+}
+
+/// Generate FFI bindings via bindgen, from the system library's headers if
+/// it is used and from the bundled ones otherwise.
+#[cfg(feature = "bindgen")]
+fn generate_bindings(system: Option<&pkg_config::Library>) {
+    let mut builder = bindgen::Builder::default().header("wrapper.h");
+    match system {
+        Some(library) => {
+            for path in &library.include_paths {
+                builder = builder.clang_arg(format!("-I{}", path.display()));
+            }
+        }
+        None => {
+            builder = builder
+                .clang_arg("-Igenerated") // pre-generated config.h, export.h
+                .clang_arg("-Ilibversion") // source headers
+                .clang_arg("-DLIBVERSION_STATIC_DEFINE");
+        }
+    }
+    let bindings = builder
         .default_enum_style(bindgen::EnumVariation::Consts)
-        .allowlist_function("version_compare.*")
-        .allowlist_var("VERSIONFLAG_.*")
+        // The header names the exact bindgen release, which would make the
+        // committed bindings stale with every patch release.
+        .disable_header_comment()
+        // Everything libversion's public headers declare (version.h, config.h
+        // and export.h), but nothing from the system headers they include.
+        .allowlist_file(r".*libversion[/\\][^/\\]*\.h")
+        // Except config.h's version macros: the pre-generated bindings would
+        // report the bundled version even when a system libversion is linked.
+        // The crate's LIBVERSION_VERSION* constants come from the build script.
+        .blocklist_item("LIBVERSION_VERSION.*")
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
         .generate()
         .expect("Unable to generate bindings");
@@ -33,3 +99,140 @@ fn main() {
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
 }
+
+/// Whether to look for a system-installed libversion: with the `system`
+/// feature, or with `LIBVERSION_SYS_USE_PKG_CONFIG` set to anything but `0`.
+#[cfg(feature = "bundled")]
+fn use_system() -> bool {
+    println!("cargo:rerun-if-env-changed=LIBVERSION_SYS_USE_PKG_CONFIG");
+    env::var_os("CARGO_FEATURE_SYSTEM").is_some()
+        || env::var_os("LIBVERSION_SYS_USE_PKG_CONFIG").is_some_and(|value| value != "0")
+}
+
+/// The version of the bundled libversion, as recorded in the pre-generated
+/// config.h.
+fn bundled_version() -> String {
+    println!("cargo:rerun-if-changed=generated/libversion/config.h");
+    let config = fs::read_to_string("generated/libversion/config.h")
+        .expect("Couldn't read generated/libversion/config.h");
+    config
+        .lines()
+        .find_map(|line| line.strip_prefix("#define LIBVERSION_VERSION "))
+        .map(|version| version.trim().trim_matches('"').to_owned())
+        .expect("LIBVERSION_VERSION is not defined in generated/libversion/config.h")
+}
+
+/// Pass the version of the libversion in use to the crate, in full and split
+/// into numeric components, for `libversion_version()` and its constants.
+fn set_version_env(version: &str) {
+    let mut components = version.split('.').map(|component| {
+        let digits = component
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(component.len());
+        component[..digits].parse::<u32>().unwrap_or(0)
+    });
+    println!("cargo:rustc-env=LIBVERSION_SYS_VERSION={version}");
+    for name in ["MAJOR", "MINOR", "PATCH"] {
+        let component = components.next().unwrap_or(0);
+        println!("cargo:rustc-env=LIBVERSION_SYS_VERSION_{name}={component}");
+    }
+}
+
+/// Copy the bundled public headers, including the pre-generated config.h
+/// and export.h, into `OUT_DIR/include/libversion`, so that dependents find
+/// them in a single include directory. Returns `OUT_DIR`.
+#[cfg(feature = "bundled")]
+fn install_headers() -> PathBuf {
+    let root = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let target = root.join("include").join("libversion");
+    fs::create_dir_all(&target).expect("Couldn't create the include directory");
+    for dir in ["libversion/libversion", "generated/libversion"] {
+        for entry in fs::read_dir(dir).expect("Couldn't read the libversion headers") {
+            let path = entry.expect("Couldn't read the libversion headers").path();
+            if path.extension().is_some_and(|extension| extension == "h") {
+                fs::copy(&path, target.join(path.file_name().unwrap()))
+                    .expect("Couldn't copy a libversion header");
+            }
+        }
+    }
+    root
+}
+
+/// Find libversion with pkg-config, requiring at least the bundled version
+/// and the same major version, so that the library behaves like the one the
+/// crate is tested against. On success pkg-config has already told Cargo
+/// how to link it; otherwise fall back to the bundled build with a warning.
+#[cfg(feature = "bundled")]
+fn system_library(bundled_version: &str) -> Option<pkg_config::Library> {
+    let major: u32 = bundled_version
+        .split('.')
+        .next()
+        .and_then(|major| major.parse().ok())
+        .expect("LIBVERSION_VERSION in config.h doesn't start with a major version");
+    let next_major = (major + 1).to_string();
+
+    match pkg_config::Config::new()
+        .range_version(bundled_version..next_major.as_str())
+        .probe("libversion")
+    {
+        Ok(library) => Some(library),
+        Err(err) => {
+            // pkg-config's errors span several lines, a warning only one.
+            let message = err.to_string();
+            let reason: Vec<&str> = message
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect();
+            println!(
+                "cargo:warning=Using the bundled libversion {bundled_version}; \
+                 no suitable system libversion found: {}",
+                reason.join(" ")
+            );
+            None
+        }
+    }
+}
+
+/// Build the bundled libversion static library using cc.
+#[cfg(feature = "bundled")]
+fn build_bundled() {
+    // The cmake-generated headers (config.h, export.h) are pre-committed under
+    // generated/ — run `bash scripts/generate-headers.sh` to regenerate them
+    // whenever the libversion submodule is updated.
+    println!("cargo:rerun-if-changed=libversion/libversion");
+    let mut build = cc::Build::new();
+    build
+        .file("libversion/libversion/compare.c")
+        .file("libversion/libversion/private/compare.c")
+        .file("libversion/libversion/private/parse.c")
+        .include("libversion") // source headers (libversion/version.h, etc.)
+        .include("generated") // pre-generated headers (libversion/config.h, libversion/export.h)
+        .define("LIBVERSION_STATIC_DEFINE", None);
+
+    // When the crate itself is built with a sanitizer (`cargo fuzz` passes
+    // `-Zsanitizer=address` by default), instrument the C code too, so the
+    // fuzzers catch memory errors inside libversion rather than only in Rust.
+    if let Ok(sanitizers) = env::var("CARGO_CFG_SANITIZE") {
+        for sanitizer in sanitizers.split(',') {
+            build.flag(format!("-fsanitize={sanitizer}"));
+        }
+        build.flag("-fno-omit-frame-pointer");
+    }
+    // Under `cargo fuzz`, also trap on undefined behaviour in C. Trapping
+    // needs no UBSan runtime, so nothing extra has to be linked.
+    // GCC spells the trap option differently from Clang.
+    if env::var_os("CARGO_CFG_FUZZING").is_some() {
+        let trap = [
+            "-fsanitize-trap=undefined",
+            "-fsanitize-undefined-trap-on-error",
+        ]
+        .into_iter()
+        .find(|flag| build.is_flag_supported(flag).unwrap_or(false));
+        if let Some(trap) = trap {
+            build.flag("-fsanitize=undefined").flag(trap);
+        }
+    }
+
+    build.compile("version");
+}
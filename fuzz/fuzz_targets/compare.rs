@@ -0,0 +1,45 @@
+//! Exercise the safe comparison API, checking that it is antisymmetric, that
+//! `Version` agrees with it, and that the C library and the Rust port in
+//! `pure` agree.
+
+#![no_main]
+
+mod input;
+
+use std::cmp::Ordering;
+use std::hash::{BuildHasher, RandomState};
+
+use libfuzzer_sys::fuzz_target;
+use libversion_sys::{Argument, Version, pure, try_compare, try_compare_with_flags};
+
+fuzz_target!(|data: &[u8]| {
+    let ([v1, v2], [f1, f2]) = input::split(data);
+    let (Ok(v1), Ok(v2)) = (std::str::from_utf8(v1), std::str::from_utf8(v2)) else {
+        return;
+    };
+
+    let result = match try_compare_with_flags(v1, v2, f1, f2) {
+        Ok(result) => result,
+        Err(err) => {
+            let version = match err.argument() {
+                Argument::V1 => v1,
+                Argument::V2 => v2,
+            };
+            assert_eq!(version.find('\0'), Some(err.nul_position()));
+            return;
+        }
+    };
+
+    assert_eq!(try_compare_with_flags(v2, v1, f2, f1), Ok(result.reverse()));
+    assert_eq!(pure::compare_with_flags(v1, v2, f1, f2), result);
+    if f1.is_empty() && f2.is_empty() {
+        assert_eq!(try_compare(v1, v2), Ok(result));
+    }
+
+    let (v1, v2) = (Version::with_flags(v1, f1), Version::with_flags(v2, f2));
+    assert_eq!(v1.cmp(&v2), result);
+    if result == Ordering::Equal {
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(&v1), state.hash_one(&v2));
+    }
+});
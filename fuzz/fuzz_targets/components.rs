@@ -0,0 +1,38 @@
+//! Exercise the Rust parser behind `components`, checking that every
+//! component it yields is a well-formed slice of the input.
+
+#![no_main]
+
+mod input;
+
+use libfuzzer_sys::fuzz_target;
+use libversion_sys::{ComponentKind, components};
+
+fuzz_target!(|data: &[u8]| {
+    let ([version], [flags]) = input::split(data);
+    let Ok(version) = std::str::from_utf8(version) else {
+        return;
+    };
+
+    let range = version.as_bytes().as_ptr_range();
+    for component in components(version, flags) {
+        match (component.number, component.alpha) {
+            (Some(number), None) => {
+                assert!(number.bytes().all(|c| c.is_ascii_digit()));
+                if component.kind == ComponentKind::Zero {
+                    assert_eq!(number, "0");
+                } else {
+                    assert_eq!(component.kind, ComponentKind::NonZero);
+                    assert!(!number.starts_with('0'));
+                    assert!(range.contains(&number.as_ptr()));
+                }
+            }
+            (None, Some(alpha)) => {
+                assert!(!alpha.is_empty());
+                assert!(alpha.bytes().all(|c| c.is_ascii_alphabetic()));
+                assert!(range.contains(&alpha.as_ptr()));
+            }
+            _ => panic!("malformed component {component:?}"),
+        }
+    }
+});
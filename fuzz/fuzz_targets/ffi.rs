@@ -0,0 +1,34 @@
+//! Feed arbitrary bytes, including invalid UTF-8 and interior null bytes,
+//! straight to libversion's C parser and comparator.
+
+#![no_main]
+
+mod input;
+
+use std::os::raw::{c_char, c_int};
+
+use libfuzzer_sys::fuzz_target;
+use libversion_sys::ffi;
+
+fn c_compare(v1: &[u8], v2: &[u8], v1_flags: c_int, v2_flags: c_int) -> c_int {
+    let v1 = [v1, b"\0"].concat();
+    let v2 = [v2, b"\0"].concat();
+    unsafe {
+        ffi::version_compare4(
+            v1.as_ptr() as *const c_char,
+            v2.as_ptr() as *const c_char,
+            v1_flags,
+            v2_flags,
+        )
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let ([v1, v2], [f1, f2]) = input::split(data);
+    let (f1, f2) = (f1.bits() as c_int, f2.bits() as c_int);
+
+    let result = c_compare(v1, v2, f1, f2);
+    assert!((-1..=1).contains(&result));
+    assert_eq!(result, -c_compare(v2, v1, f2, f1));
+    assert_eq!(c_compare(v1, v1, f1, f1), 0);
+});
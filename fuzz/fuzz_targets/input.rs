@@ -0,0 +1,19 @@
+//! Input format shared by the fuzz targets, chosen so that seeds are plain
+//! text: one version string per line, optionally followed by a line of flags.
+//! Each byte of that line holds the flags of one version in its low four
+//! bits, so `0`-`9` and `:;<=>?` cover every combination.
+
+use libversion_sys::VersionFlags;
+
+/// Split `data` into `N` version strings and their flags. Missing versions
+/// are empty and missing flags are empty.
+pub fn split<const N: usize>(data: &[u8]) -> ([&[u8]; N], [VersionFlags; N]) {
+    let mut lines = data.splitn(N + 1, |&c| c == b'\n');
+    let versions = [(); N].map(|()| lines.next().unwrap_or_default());
+    let flag_bytes = lines.next().unwrap_or_default();
+    let flags = std::array::from_fn(|i| {
+        let bits = flag_bytes.get(i).map_or(0, |&c| u32::from(c) & 0xf);
+        VersionFlags::from_bits_truncate(bits)
+    });
+    (versions, flags)
+}
@@ -0,0 +1,40 @@
+//! Exercise `VersionReq::parse`, checking that errors point at whole
+//! characters of the input, that a parsed requirement prints as a string
+//! that parses back to it, and that matching a version does not panic.
+
+#![no_main]
+
+mod input;
+
+use libfuzzer_sys::fuzz_target;
+use libversion_sys::VersionReq;
+
+fuzz_target!(|data: &[u8]| {
+    let ([req, version], [_, flags]) = input::split(data);
+    let Ok(req) = std::str::from_utf8(req) else {
+        return;
+    };
+
+    let parsed = match VersionReq::parse(req) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            let span = err.span();
+            assert!(span.start <= span.end && span.end <= req.len(), "{err}");
+            assert!(req.is_char_boundary(span.start), "{err}");
+            assert!(req.is_char_boundary(span.end), "{err}");
+            return;
+        }
+    };
+
+    let printed = parsed.to_string();
+    match VersionReq::parse(&printed) {
+        Ok(reparsed) => assert_eq!(reparsed, parsed, "{req:?} printed as {printed:?}"),
+        Err(err) => panic!("{req:?} printed as {printed:?}, which fails to parse: {err}"),
+    }
+
+    if let Ok(version) = std::str::from_utf8(version)
+        && !version.contains('\0')
+    {
+        parsed.matches_with_flags(version, flags);
+    }
+});
@@ -0,0 +1,18 @@
+pub const VERSIONFLAG_P_IS_PATCH: u32 = 1;
+pub const VERSIONFLAG_ANY_IS_PATCH: u32 = 2;
+pub const VERSIONFLAG_LOWER_BOUND: u32 = 4;
+pub const VERSIONFLAG_UPPER_BOUND: u32 = 8;
+unsafe extern "C" {
+    pub fn version_compare2(
+        v1: *const ::std::os::raw::c_char,
+        v2: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn version_compare4(
+        v1: *const ::std::os::raw::c_char,
+        v2: *const ::std::os::raw::c_char,
+        v1_flags: ::std::os::raw::c_int,
+        v2_flags: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+#
+# Regenerate the pre-committed FFI bindings (generated/bindings.rs) with
+# bindgen, so that the normal build never needs libclang installed.
+#
+# Usage:  bash scripts/generate-bindings.sh
+#
+# Run this script whenever the libversion submodule is updated, after
+# scripts/generate-headers.sh. Requires libclang.
+
+set -euo pipefail
+
+SCRIPT_DIR="$(cd "$(dirname "$0")" && pwd)"
+PROJECT_ROOT="$(cd "$SCRIPT_DIR/.." && pwd)"
+
+echo "Running bindgen through the build script..."
+OUT_DIR="$(
+    cd "$PROJECT_ROOT" &&
+    cargo build --features bindgen --message-format=json |
+    grep '"reason":"build-script-executed"' |
+    grep 'libversion-sys' |
+    sed -n 's/.*"out_dir":"\([^"]*\)".*/\1/p' |
+    tail -n 1
+)"
+
+if [ -z "$OUT_DIR" ]; then
+    echo "Couldn't find the build script's output directory" >&2
+    exit 1
+fi
+
+cp "$OUT_DIR/bindings.rs" "$PROJECT_ROOT/generated/bindings.rs"
+
+echo "Bindings updated in generated/bindings.rs"
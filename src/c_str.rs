@@ -0,0 +1,36 @@
+//! Null-terminated views of Rust strings for the C API, without heap
+//! allocation in the common case.
+
+use std::ffi::{CStr, CString};
+
+use crate::{Argument, NulError};
+
+/// Version strings shorter than this are copied onto the stack; longer ones
+/// fall back to a heap-allocated [`CString`]. Real-world versions are well
+/// under this limit.
+const STACK_BUF_LEN: usize = 256;
+
+/// Call `f` with a null-terminated copy of `s`.
+///
+/// Fails with a [`NulError`] for `argument` if `s` contains a null byte, since
+/// C would silently truncate the string there.
+pub(crate) fn with_c_str<R>(
+    s: &str,
+    argument: Argument,
+    f: impl FnOnce(&CStr) -> R,
+) -> Result<R, NulError> {
+    NulError::check(s, argument)?;
+    let bytes = s.as_bytes();
+
+    if bytes.len() < STACK_BUF_LEN {
+        let mut buf = [0u8; STACK_BUF_LEN];
+        buf[..bytes.len()].copy_from_slice(bytes);
+        // SAFETY: `bytes` contains no null byte and is followed by one.
+        let c_str = unsafe { CStr::from_bytes_with_nul_unchecked(&buf[..=bytes.len()]) };
+        Ok(f(c_str))
+    } else {
+        // SAFETY: checked for null bytes above.
+        let c_string = unsafe { CString::from_vec_unchecked(bytes.to_vec()) };
+        Ok(f(&c_string))
+    }
+}
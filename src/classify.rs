@@ -0,0 +1,197 @@
+use crate::VersionFlags;
+use crate::parse::{ComponentKind, components};
+
+/// Whether a version is a release, a pre-release or a post-release, as
+/// returned by [`classify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Classification<'a> {
+    /// A plain release such as `1.0`, `1.0.1` or `1.0a`.
+    Release,
+    /// A pre-release such as `1.0alpha1`, `2.0-rc.1` or `1.0-SNAPSHOT`.
+    PreRelease {
+        /// The letter run that makes it a pre-release, as written.
+        keyword: &'a str,
+    },
+    /// A post-release such as `1.0patch1`, `1.0.post2` or, with
+    /// [`P_IS_PATCH`](VersionFlags::P_IS_PATCH), `9.6p1`.
+    PostRelease {
+        /// The letter run that makes it a post-release, as written.
+        keyword: &'a str,
+    },
+}
+
+impl Classification<'_> {
+    /// Whether this is a [`PreRelease`](Classification::PreRelease).
+    pub fn is_prerelease(&self) -> bool {
+        matches!(self, Classification::PreRelease { .. })
+    }
+
+    /// Whether this is a [`Release`](Classification::Release) or a
+    /// [`PostRelease`](Classification::PostRelease).
+    pub fn is_stable(&self) -> bool {
+        !self.is_prerelease()
+    }
+}
+
+/// Classify `version`, interpreted with `flags`, as a release, pre-release
+/// or post-release.
+///
+/// The first pre-release or post-release keyword decides, using the same
+/// rules libversion ranks components by: `alpha`, `beta`, `rc`, `pre*` and
+/// unknown letter runs make a pre-release, and `post*`, `patch*`, `pl` and
+/// `errata` a post-release. [`P_IS_PATCH`](VersionFlags::P_IS_PATCH) and
+/// [`ANY_IS_PATCH`](VersionFlags::ANY_IS_PATCH) move `p` and unknown letter
+/// runs to post-releases. A letter directly after a number, as in `1.0a`, is
+/// a letter suffix and leaves the version a release.
+///
+/// This agrees with [`compare_with_flags`](crate::compare_with_flags): a
+/// pre-release sorts before the version cut off right before its keyword, a
+/// post-release after it. The bound flags don't affect the result. Like the
+/// C library, parsing stops at the first null byte.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{Classification, VersionFlags, classify};
+///
+/// let none = VersionFlags::empty();
+/// assert_eq!(classify("1.0.1", none), Classification::Release);
+/// assert_eq!(classify("1.0a", none), Classification::Release);
+/// assert_eq!(classify("2.0-RC.1", none), Classification::PreRelease { keyword: "RC" });
+/// assert_eq!(classify("1.0pl2", none), Classification::PostRelease { keyword: "pl" });
+/// assert_eq!(classify("9.6p1", none), Classification::PreRelease { keyword: "p" });
+/// assert_eq!(
+///     classify("9.6p1", VersionFlags::P_IS_PATCH),
+///     Classification::PostRelease { keyword: "p" },
+/// );
+/// ```
+pub fn classify(version: &str, flags: VersionFlags) -> Classification<'_> {
+    for component in components(version, flags) {
+        match (component.kind, component.alpha) {
+            (ComponentKind::PreRelease, Some(keyword)) => {
+                return Classification::PreRelease { keyword };
+            }
+            (ComponentKind::PostRelease, Some(keyword)) => {
+                return Classification::PostRelease { keyword };
+            }
+            _ => {}
+        }
+    }
+    Classification::Release
+}
+
+/// Whether `version`, interpreted with `flags`, is a pre-release.
+///
+/// Shorthand for `classify(version, flags).is_prerelease()`; see
+/// [`classify`].
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, is_prerelease};
+///
+/// assert!(is_prerelease("1.0beta2", VersionFlags::empty()));
+/// assert!(!is_prerelease("1.0", VersionFlags::empty()));
+/// ```
+pub fn is_prerelease(version: &str, flags: VersionFlags) -> bool {
+    classify(version, flags).is_prerelease()
+}
+
+/// Whether `version`, interpreted with `flags`, is a release or a
+/// post-release, i.e. not a pre-release.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, is_stable};
+///
+/// let versions = ["1.0", "1.1rc1", "1.0patch1", "1.1-dev"];
+/// let stable: Vec<&str> = versions
+///     .into_iter()
+///     .filter(|v| is_stable(v, VersionFlags::empty()))
+///     .collect();
+/// assert_eq!(stable, ["1.0", "1.0patch1"]);
+/// ```
+pub fn is_stable(version: &str, flags: VersionFlags) -> bool {
+    classify(version, flags).is_stable()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::*;
+    use crate::pure;
+
+    #[test]
+    fn keywords() {
+        let none = VersionFlags::empty();
+        let pre = |keyword| Classification::PreRelease { keyword };
+        let post = |keyword| Classification::PostRelease { keyword };
+        assert_eq!(classify("", none), Classification::Release);
+        assert_eq!(classify("1.0b", none), Classification::Release);
+        assert_eq!(classify("1.0.b", none), pre("b"));
+        assert_eq!(classify("1.0b1", none), pre("b"));
+        assert_eq!(classify("alpha", none), pre("alpha"));
+        assert_eq!(classify("1.0preview3", none), pre("preview"));
+        assert_eq!(classify("1.0-SNAPSHOT", none), pre("SNAPSHOT"));
+        assert_eq!(
+            classify("1.0-SNAPSHOT", VersionFlags::ANY_IS_PATCH),
+            post("SNAPSHOT")
+        );
+        assert_eq!(
+            classify("1.0x", VersionFlags::ANY_IS_PATCH),
+            Classification::Release
+        );
+        assert_eq!(classify("1.0patchlevel", none), post("patchlevel"));
+        assert_eq!(classify("1.0errata.rc1", none), post("errata"));
+        assert_eq!(classify("1.0rc1.post1", none), pre("rc"));
+        assert_eq!(classify("1.0\0rc1", none), Classification::Release);
+    }
+
+    #[test]
+    fn agrees_with_compare() {
+        let versions = [
+            "1.0",
+            "1.0a",
+            "1.0.a",
+            "1.0a1",
+            "1.0alpha",
+            "1.0.RC.1",
+            "1.0p1",
+            "1.0pl",
+            "1.0x",
+            "1.0.x",
+            "1.0post1.beta",
+            "1.0beta.post1",
+            "a",
+            "p1",
+            "1.0.0.0-dev",
+        ];
+        let flags = [
+            VersionFlags::empty(),
+            VersionFlags::P_IS_PATCH,
+            VersionFlags::ANY_IS_PATCH,
+            VersionFlags::LOWER_BOUND,
+            VersionFlags::P_IS_PATCH | VersionFlags::UPPER_BOUND,
+        ];
+        for version in versions {
+            for flags in flags {
+                let plain = flags - VersionFlags::LOWER_BOUND - VersionFlags::UPPER_BOUND;
+                let (keyword, expected) = match classify(version, flags) {
+                    Classification::Release => continue,
+                    Classification::PreRelease { keyword } => (keyword, Ordering::Less),
+                    Classification::PostRelease { keyword } => (keyword, Ordering::Greater),
+                };
+                // Cut the version off right before its keyword, and compare
+                // with it as a plain version rather than a bound.
+                let release = &version[..keyword.as_ptr() as usize - version.as_ptr() as usize];
+                assert_eq!(
+                    pure::compare_with_flags(version, release, flags, plain),
+                    expected,
+                    "{version:?} with {flags:?}",
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+/// Identifies which argument of a comparison an error refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Argument {
+    /// The first version string.
+    V1,
+    /// The second version string.
+    V2,
+}
+
+impl fmt::Display for Argument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Argument::V1 => "v1",
+            Argument::V2 => "v2",
+        })
+    }
+}
+
+/// Error returned by [`try_compare`](crate::try_compare) and
+/// [`try_compare_with_flags`](crate::try_compare_with_flags) when a version
+/// string contains an interior null byte and so cannot be passed to C.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{Argument, try_compare};
+///
+/// let err = try_compare("1.0", "1.\01").unwrap_err();
+/// assert_eq!(err.argument(), Argument::V2);
+/// assert_eq!(err.nul_position(), 2);
+/// assert_eq!(err.to_string(), "v2 contains an interior null byte at position 2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NulError {
+    argument: Argument,
+    position: usize,
+}
+
+impl NulError {
+    pub(crate) fn new(argument: Argument, position: usize) -> Self {
+        NulError { argument, position }
+    }
+
+    /// Fail if `version`, passed as `argument`, contains a null byte.
+    pub(crate) fn check(version: &str, argument: Argument) -> Result<(), Self> {
+        match version.bytes().position(|c| c == 0) {
+            Some(position) => Err(NulError::new(argument, position)),
+            None => Ok(()),
+        }
+    }
+
+    /// The argument that contained the null byte.
+    pub fn argument(&self) -> Argument {
+        self.argument
+    }
+
+    /// The byte offset of the first null byte within that argument.
+    pub fn nul_position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for NulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} contains an interior null byte at position {}",
+            self.argument, self.position
+        )
+    }
+}
+
+impl Error for NulError {}
+
+/// The reason a [`VersionReq`](crate::VersionReq) failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReqErrorKind {
+    /// A predicate was expected, e.g. after `,` or `||` or in an empty string.
+    ExpectedPredicate,
+    /// An operator is not followed by a version.
+    MissingVersion,
+    /// A character that cannot appear at this point.
+    UnexpectedChar(char),
+    /// A run of operator characters that is not one of `=`, `!=`, `<`, `<=`,
+    /// `>` or `>=`.
+    InvalidOperator(String),
+    /// A `*` that does not replace the last component of a version, as in
+    /// `1.*.2` or `1*`.
+    MisplacedWildcard,
+}
+
+impl fmt::Display for ReqErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReqErrorKind::ExpectedPredicate => f.write_str("expected a version predicate"),
+            ReqErrorKind::MissingVersion => f.write_str("operator is not followed by a version"),
+            ReqErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {c:?}"),
+            ReqErrorKind::InvalidOperator(op) => write!(f, "invalid operator `{op}`"),
+            ReqErrorKind::MisplacedWildcard => {
+                f.write_str("`*` may only replace the last component of a version")
+            }
+        }
+    }
+}
+
+/// Error returned by [`VersionReq::parse`](crate::VersionReq::parse).
+///
+/// Besides the [kind](ParseReqError::kind) of error, it carries the
+/// [span](ParseReqError::span) of the offending input, as byte offsets into
+/// the requirement string, for pointing at it in diagnostics.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{ReqErrorKind, VersionReq};
+///
+/// let err = VersionReq::parse(">=1.0, =>2.0").unwrap_err();
+/// assert_eq!(err.kind(), &ReqErrorKind::InvalidOperator("=>".to_owned()));
+/// assert_eq!(err.span(), 7..9);
+/// assert_eq!(err.to_string(), "invalid operator `=>` at 7..9");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseReqError {
+    kind: ReqErrorKind,
+    span: Range<usize>,
+}
+
+impl ParseReqError {
+    pub(crate) fn new(kind: ReqErrorKind, span: Range<usize>) -> Self {
+        ParseReqError { kind, span }
+    }
+
+    /// What went wrong.
+    pub fn kind(&self) -> &ReqErrorKind {
+        &self.kind
+    }
+
+    /// The byte range of the requirement string the error refers to. It is
+    /// empty when the error is about missing input, such as at the end of
+    /// the string.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl fmt::Display for ParseReqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+impl Error for ParseReqError {}
@@ -0,0 +1,284 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::VersionFlags;
+use crate::parse::{Component, ComponentKind, components};
+use crate::pure::compare_components;
+
+/// The rule that decided a comparison, as reported by
+/// [`Explanation::decided_by`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// One version ran out of components and its padding, zero or a bound,
+    /// ranked differently from the other version's component, or both ran
+    /// out and only one of them is a bound.
+    Padding,
+    /// The components are of different kinds and rank differently, such as a
+    /// pre-release keyword against a number.
+    Rank,
+    /// A letter suffix, as in `1.0a`, against a component of another kind or
+    /// a different letter suffix.
+    LetterSuffix,
+    /// Two keywords of the same kind with different first letters, such as
+    /// `alpha` and `beta`.
+    Letter,
+    /// Two numbers with different values.
+    Numeric,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Rule::Padding => "padding",
+            Rule::Rank => "component rank",
+            Rule::LetterSuffix => "letter suffix",
+            Rule::Letter => "first letter",
+            Rule::Numeric => "numeric value",
+        })
+    }
+}
+
+/// One pair of components compared while explaining a comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Step<'a> {
+    /// The component of the first version.
+    pub left: Component<'a>,
+    /// The component of the second version.
+    pub right: Component<'a>,
+    /// Whether `left` is the padding past the end of the first version.
+    pub left_is_padding: bool,
+    /// Whether `right` is the padding past the end of the second version.
+    pub right_is_padding: bool,
+    /// How `left` compares to `right`.
+    pub ordering: Ordering,
+}
+
+impl Step<'_> {
+    fn rule(&self) -> Option<Rule> {
+        let (left, right) = (self.left.kind, self.right.kind);
+        let is_number = |kind| matches!(kind, ComponentKind::Zero | ComponentKind::NonZero);
+        Some(match self.ordering {
+            Ordering::Equal => return None,
+            _ if self.left_is_padding || self.right_is_padding => Rule::Padding,
+            _ if is_number(left) && is_number(right) => Rule::Numeric,
+            _ if left == ComponentKind::LetterSuffix || right == ComponentKind::LetterSuffix => {
+                Rule::LetterSuffix
+            }
+            _ if left != right => Rule::Rank,
+            _ => Rule::Letter,
+        })
+    }
+}
+
+/// A trace of how libversion compares two versions, created by [`explain`].
+///
+/// The [`Display`](fmt::Display) implementation lists every compared pair
+/// with the kind of each component, followed by the rule that decided the
+/// result.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Explanation<'a> {
+    v1: &'a str,
+    v2: &'a str,
+    steps: Vec<Step<'a>>,
+}
+
+impl<'a> Explanation<'a> {
+    /// The result of the comparison.
+    pub fn ordering(&self) -> Ordering {
+        self.steps
+            .last()
+            .map_or(Ordering::Equal, |step| step.ordering)
+    }
+
+    /// Every pair of components compared, in order. The comparison stops at
+    /// the first pair that differs, and ends with both paddings when every
+    /// pair is equal.
+    pub fn steps(&self) -> &[Step<'a>] {
+        &self.steps
+    }
+
+    /// The first pair of components that differ, or `None` if the versions
+    /// are equal.
+    pub fn first_difference(&self) -> Option<&Step<'a>> {
+        self.steps
+            .last()
+            .filter(|step| step.ordering != Ordering::Equal)
+    }
+
+    /// The rule that decided the comparison, or `None` if the versions are
+    /// equal.
+    pub fn decided_by(&self) -> Option<Rule> {
+        self.first_difference().and_then(Step::rule)
+    }
+}
+
+fn kind_name(kind: ComponentKind) -> &'static str {
+    match kind {
+        ComponentKind::LowerBound => "lower bound",
+        ComponentKind::PreRelease => "pre-release",
+        ComponentKind::Zero => "zero",
+        ComponentKind::PostRelease => "post-release",
+        ComponentKind::NonZero => "number",
+        ComponentKind::LetterSuffix => "letter suffix",
+        ComponentKind::UpperBound => "upper bound",
+    }
+}
+
+fn write_component(
+    f: &mut fmt::Formatter<'_>,
+    component: &Component<'_>,
+    padding: bool,
+) -> fmt::Result {
+    let kind = kind_name(component.kind);
+    match component.number.or(component.alpha) {
+        Some(text) if !padding => write!(f, "{text} ({kind})"),
+        _ => write!(f, "padding ({kind})"),
+    }
+}
+
+fn symbol(ordering: Ordering) -> &'static str {
+    match ordering {
+        Ordering::Less => "<",
+        Ordering::Equal => "=",
+        Ordering::Greater => ">",
+    }
+}
+
+impl fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?} {} {:?}", self.v1, symbol(self.ordering()), self.v2)?;
+        for (i, step) in self.steps.iter().enumerate() {
+            write!(f, "  {}: ", i + 1)?;
+            write_component(f, &step.left, step.left_is_padding)?;
+            write!(f, " {} ", symbol(step.ordering))?;
+            write_component(f, &step.right, step.right_is_padding)?;
+            writeln!(f)?;
+        }
+        match self.decided_by() {
+            Some(rule) => write!(f, "decided by {rule} at component {}", self.steps.len()),
+            None => f.write_str("all components are equal"),
+        }
+    }
+}
+
+/// Compare two versions with per-version flags like
+/// [`compare_with_flags`](crate::compare_with_flags), recording how the
+/// result was reached.
+///
+/// The returned [`Explanation`] holds every pair of components compared,
+/// the first pair that differs and the rule that decided it, and displays
+/// as a readable trace for bug reports. Like the C library, parsing stops at
+/// the first null byte.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use libversion_sys::{Rule, VersionFlags, explain};
+///
+/// let none = VersionFlags::empty();
+/// let explanation = explain("1.0p1", "1.0", none, none);
+/// assert_eq!(explanation.ordering(), Ordering::Less);
+/// assert_eq!(explanation.decided_by(), Some(Rule::Padding));
+/// assert_eq!(
+///     explanation.to_string(),
+///     "\"1.0p1\" < \"1.0\"
+///   1: 1 (number) = 1 (number)
+///   2: 0 (zero) = 0 (zero)
+///   3: p (pre-release) < padding (zero)
+/// decided by padding at component 3",
+/// );
+///
+/// let explanation = explain("1.0p1", "1.0", VersionFlags::P_IS_PATCH, none);
+/// assert_eq!(explanation.ordering(), Ordering::Greater);
+/// ```
+pub fn explain<'a>(
+    v1: &'a str,
+    v2: &'a str,
+    v1_flags: VersionFlags,
+    v2_flags: VersionFlags,
+) -> Explanation<'a> {
+    let padding1 = Component::padding(v1_flags);
+    let padding2 = Component::padding(v2_flags);
+    let mut components1 = components(v1, v1_flags);
+    let mut components2 = components(v2, v2_flags);
+
+    let mut steps = Vec::new();
+    loop {
+        let (c1, c2) = (components1.next(), components2.next());
+        let done = c1.is_none() && c2.is_none();
+        let (left, right) = (c1.unwrap_or(padding1), c2.unwrap_or(padding2));
+        let step = Step {
+            left,
+            right,
+            left_is_padding: c1.is_none(),
+            right_is_padding: c2.is_none(),
+            ordering: compare_components(&left, &right),
+        };
+        steps.push(step);
+        if done || step.ordering != Ordering::Equal {
+            return Explanation { v1, v2, steps };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decided_by(v1: &str, v2: &str, v1_flags: VersionFlags) -> Option<Rule> {
+        explain(v1, v2, v1_flags, VersionFlags::empty()).decided_by()
+    }
+
+    #[test]
+    fn rules() {
+        let none = VersionFlags::empty();
+        assert_eq!(decided_by("1.0", "1.0.0", none), None);
+        assert_eq!(decided_by("1.1", "1.0", none), Some(Rule::Numeric));
+        assert_eq!(decided_by("1.10", "1.9", none), Some(Rule::Numeric));
+        assert_eq!(decided_by("1.0a", "1.0.1", none), Some(Rule::LetterSuffix));
+        assert_eq!(decided_by("1.0a", "1.0b", none), Some(Rule::LetterSuffix));
+        assert_eq!(
+            decided_by("1.0alpha1", "1.0beta1", none),
+            Some(Rule::Letter)
+        );
+        assert_eq!(decided_by("1.0alpha1", "1.0.1", none), Some(Rule::Rank));
+        assert_eq!(decided_by("1.0patch1", "1.0rc1", none), Some(Rule::Rank));
+        assert_eq!(decided_by("1.0", "1.0a", none), Some(Rule::Padding));
+        assert_eq!(
+            decided_by("1.0", "1.0", VersionFlags::LOWER_BOUND),
+            Some(Rule::Padding)
+        );
+    }
+
+    #[test]
+    fn steps() {
+        let none = VersionFlags::empty();
+        let explanation = explain("1.0", "1", none, none);
+        assert_eq!(explanation.ordering(), Ordering::Equal);
+        assert_eq!(explanation.first_difference(), None);
+        let padding: Vec<_> = explanation
+            .steps()
+            .iter()
+            .map(|s| (s.left_is_padding, s.right_is_padding))
+            .collect();
+        assert_eq!(padding, [(false, false), (false, true), (true, true)]);
+
+        let explanation = explain("2", "1.5", none, none);
+        assert_eq!(explanation.steps().len(), 1);
+        assert_eq!(
+            explanation.first_difference().unwrap().left.number,
+            Some("2")
+        );
+
+        let explanation = explain("1.0", "1.0\x001", VersionFlags::UPPER_BOUND, none);
+        assert_eq!(
+            explanation.to_string(),
+            "\"1.0\" > \"1.0\\01\"
+  1: 1 (number) = 1 (number)
+  2: 0 (zero) = 0 (zero)
+  3: padding (upper bound) > padding (zero)
+decided by padding at component 3",
+        );
+    }
+}
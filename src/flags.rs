@@ -0,0 +1,30 @@
+bitflags::bitflags! {
+    /// Flags altering how libversion interprets a version string.
+    ///
+    /// Flags apply to a single side of a comparison, so the safe comparison
+    /// functions take one set per version. Combine flags with `|`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use libversion_sys::VersionFlags;
+    ///
+    /// let flags = VersionFlags::P_IS_PATCH | VersionFlags::LOWER_BOUND;
+    /// assert_eq!(format!("{flags:?}"), "VersionFlags(P_IS_PATCH | LOWER_BOUND)");
+    /// assert_eq!(VersionFlags::default(), VersionFlags::empty());
+    /// ```
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct VersionFlags: u32 {
+        // Values from libversion/version.h, spelled out so that they are
+        // available without the C library, when `bundled` is off.
+
+        /// Treat `p` as patch (post-release) instead of pre-release.
+        const P_IS_PATCH = 0x1;
+        /// Treat any letter sequence as post-release.
+        const ANY_IS_PATCH = 0x2;
+        /// Derive the lowest possible version with the given prefix.
+        const LOWER_BOUND = 0x4;
+        /// Derive the highest possible version with the given prefix.
+        const UPPER_BOUND = 0x8;
+    }
+}
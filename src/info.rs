@@ -0,0 +1,63 @@
+/// Parse a version component passed in by the build script.
+const fn component(value: &str) -> u32 {
+    match u32::from_str_radix(value, 10) {
+        Ok(component) => component,
+        Err(_) => panic!("the build script passed a non-numeric version component"),
+    }
+}
+
+/// The version of libversion this crate uses, such as `"3.0.4"`.
+///
+/// This is the bundled copy's version, or with the `system` feature the
+/// version pkg-config reported for the system library. Without the `bundled`
+/// feature it is the version the Rust port follows. It is determined at
+/// build time: libversion has no function that reports its version at run
+/// time.
+pub const LIBVERSION_VERSION: &str = env!("LIBVERSION_SYS_VERSION");
+
+/// The major component of [`LIBVERSION_VERSION`].
+pub const LIBVERSION_VERSION_MAJOR: u32 = component(env!("LIBVERSION_SYS_VERSION_MAJOR"));
+
+/// The minor component of [`LIBVERSION_VERSION`].
+pub const LIBVERSION_VERSION_MINOR: u32 = component(env!("LIBVERSION_SYS_VERSION_MINOR"));
+
+/// The patch component of [`LIBVERSION_VERSION`].
+pub const LIBVERSION_VERSION_PATCH: u32 = component(env!("LIBVERSION_SYS_VERSION_PATCH"));
+
+/// The version of libversion this crate uses; see [`LIBVERSION_VERSION`].
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{LIBVERSION_VERSION_MAJOR, compare, libversion_version};
+///
+/// assert_eq!(LIBVERSION_VERSION_MAJOR, 3);
+/// // Versions compare with libversion itself, of course.
+/// assert!(compare(libversion_version(), "3.0").is_ge());
+/// ```
+pub fn libversion_version() -> &'static str {
+    LIBVERSION_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn components_match_the_version() {
+        let numeric = format!(
+            "{LIBVERSION_VERSION_MAJOR}.{LIBVERSION_VERSION_MINOR}.{LIBVERSION_VERSION_PATCH}"
+        );
+        assert_eq!(
+            crate::compare(&numeric, libversion_version()),
+            std::cmp::Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn same_major_as_bundled() {
+        let config = include_str!("../generated/libversion/config.h");
+        let major = format!("#define LIBVERSION_VERSION_MAJOR {LIBVERSION_VERSION_MAJOR}\n");
+        assert!(config.contains(&major), "expected {major:?} in config.h");
+    }
+}
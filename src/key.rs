@@ -0,0 +1,405 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+use crate::VersionFlags;
+use crate::parse::{ComponentKind, components};
+
+/// A version parsed once into the component sequence libversion compares.
+///
+/// Comparing two `SortKey`s gives the same result as
+/// [`compare_with_flags`](crate::compare_with_flags) on the versions and
+/// flags they were built from, without parsing either string again. Build
+/// keys once when a collection is compared many times, as in a sort;
+/// [`sort_versions`](crate::sort_versions) does this internally.
+///
+/// Like the C library, parsing stops at the first null byte.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{SortKey, VersionFlags};
+///
+/// let mut keys: Vec<SortKey> = ["1.10", "1.2", "1.0alpha1"]
+///     .iter()
+///     .map(|v| SortKey::new(v, VersionFlags::empty()))
+///     .collect();
+/// keys.sort();
+/// assert_eq!(keys[0], SortKey::new("1.0a1", VersionFlags::empty()));
+/// assert!(keys[2] > SortKey::new("1.9", VersionFlags::empty()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SortKey {
+    /// The first components packed so that comparing prefixes agrees with
+    /// comparing keys whenever the prefixes differ; see [`SortKey::pack_prefix`].
+    prefix: u128,
+    parts: Vec<Part>,
+    /// The digits of numbers too long for [`Value::Small`], back to back.
+    long_numbers: String,
+    flags: VersionFlags,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Part {
+    kind: ComponentKind,
+    value: Value,
+}
+
+/// The compared value of a component.
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    /// A number of up to 19 digits, or the lowercased first letter of a
+    /// letter run.
+    Small(u64),
+    /// A longer number, without leading zeroes, at `start..end` in
+    /// `long_numbers`. It is greater than any `Small` number.
+    Long { start: usize, end: usize },
+}
+
+/// Numbers with up to this many digits fit in a `u64`.
+const SMALL_DIGITS: usize = 19;
+
+/// How many components [`SortKey::pack_prefix`] packs, and the bits given to the
+/// value of each after its three bits of kind.
+const PREFIX_PARTS: usize = 3;
+const PREFIX_VALUE_BITS: u32 = 39;
+
+impl Part {
+    fn padding(flags: VersionFlags) -> Self {
+        Part {
+            kind: ComponentKind::padding(flags),
+            value: Value::Small(0),
+        }
+    }
+}
+
+impl SortKey {
+    /// Parse `version`, to be interpreted with `flags`.
+    pub fn new(version: &str, flags: VersionFlags) -> Self {
+        let mut long_numbers = String::new();
+        let parts = components(version, flags)
+            .map(|component| {
+                let value = match (component.number, component.alpha) {
+                    (Some(number), _) if number.len() <= SMALL_DIGITS => {
+                        Value::Small(number.parse().expect("a run of ASCII digits"))
+                    }
+                    (Some(number), _) => {
+                        let start = long_numbers.len();
+                        long_numbers.push_str(number);
+                        Value::Long {
+                            start,
+                            end: long_numbers.len(),
+                        }
+                    }
+                    (None, Some(alpha)) => {
+                        Value::Small(alpha.as_bytes()[0].to_ascii_lowercase().into())
+                    }
+                    (None, None) => Value::Small(0),
+                };
+                Part {
+                    kind: component.kind,
+                    value,
+                }
+            })
+            .collect::<Vec<_>>();
+        SortKey {
+            prefix: SortKey::pack_prefix(&parts, flags),
+            parts,
+            long_numbers,
+            flags,
+        }
+    }
+
+    /// The flags the version was parsed with.
+    pub fn flags(&self) -> VersionFlags {
+        self.flags
+    }
+
+    /// Pack the kind and value of the first components, padded as in a
+    /// comparison, into an integer. A value too large for its bits is
+    /// saturated and ends the prefix, so that a prefix never orders two keys
+    /// differently from a full comparison, though equal prefixes leave the
+    /// order undecided.
+    fn pack_prefix(parts: &[Part], flags: VersionFlags) -> u128 {
+        let padding = Part::padding(flags);
+        let max_value = (1 << PREFIX_VALUE_BITS) - 1;
+        let mut prefix = 0;
+        let mut saturated = false;
+        for i in 0..PREFIX_PARTS {
+            prefix <<= PREFIX_VALUE_BITS + 3;
+            if saturated {
+                continue;
+            }
+            let part = parts.get(i).unwrap_or(&padding);
+            let value = match part.value {
+                Value::Small(value) if value < max_value => value,
+                _ => {
+                    saturated = true;
+                    max_value
+                }
+            };
+            prefix |= (part.kind as u128) << PREFIX_VALUE_BITS | u128::from(value);
+        }
+        prefix
+    }
+
+    fn long_number(&self, start: usize, end: usize) -> &str {
+        &self.long_numbers[start..end]
+    }
+
+    /// Compare two parts like `pure::compare_components`. Components of the
+    /// same kind always hold the same sort of value.
+    fn compare_parts(&self, a: &Part, other: &SortKey, b: &Part) -> Ordering {
+        a.kind.cmp(&b.kind).then_with(|| match (a.value, b.value) {
+            (Value::Small(x), Value::Small(y)) => x.cmp(&y),
+            (Value::Small(_), Value::Long { .. }) => Ordering::Less,
+            (Value::Long { .. }, Value::Small(_)) => Ordering::Greater,
+            (Value::Long { start: s1, end: e1 }, Value::Long { start: s2, end: e2 }) => {
+                let (n1, n2) = (self.long_number(s1, e1), other.long_number(s2, e2));
+                n1.len().cmp(&n2.len()).then_with(|| n1.cmp(n2))
+            }
+        })
+    }
+}
+
+impl PartialEq for SortKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortKey {}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.prefix != other.prefix {
+            return self.prefix.cmp(&other.prefix);
+        }
+        // The same walk as `pure::compare_with_flags`, over parsed parts.
+        let (padding1, padding2) = (Part::padding(self.flags), Part::padding(other.flags));
+        for i in 0..self.parts.len().max(other.parts.len()) {
+            let a = self.parts.get(i).unwrap_or(&padding1);
+            let b = other.parts.get(i).unwrap_or(&padding2);
+            match self.compare_parts(a, other, b) {
+                Ordering::Equal => {}
+                unequal => return unequal,
+            }
+        }
+        self.compare_parts(&padding1, other, &padding2)
+    }
+}
+
+impl Hash for SortKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Like `parse::hash_version`: trailing padding is not hashed.
+        let padding = ComponentKind::padding(self.flags);
+        let mut zeroes = 0usize;
+        for part in &self.parts {
+            if part.kind == padding {
+                zeroes += 1;
+                continue;
+            }
+            for _ in 0..zeroes {
+                ComponentKind::Zero.hash(state);
+            }
+            zeroes = 0;
+            part.kind.hash(state);
+            match part.value {
+                Value::Small(value) => value.hash(state),
+                Value::Long { start, end } => self.long_number(start, end).hash(state),
+            }
+        }
+        padding.hash(state);
+    }
+}
+
+/// Encode `version`, interpreted with `flags`, into bytes whose
+/// lexicographic order is libversion's order.
+///
+/// For any two versions, comparing their encodings byte by byte (as
+/// `memcmp`, `Ord` on `[u8]`, RocksDB's default comparator or PostgreSQL's
+/// `bytea` do) gives the same result as
+/// [`compare_with_flags`](crate::compare_with_flags) on the versions and
+/// their flags. In particular, versions compare equal exactly when their
+/// encodings are identical, so `1.0`, `1.0.0` and `1.00` all encode the same.
+/// The encoding is stable across releases of this crate.
+///
+/// Like the C library, parsing stops at the first null byte.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, encode_sort_key};
+///
+/// let key = |v| encode_sort_key(v, VersionFlags::empty());
+/// assert!(key("1.0alpha1") < key("1.0"));
+/// assert!(key("1.0") < key("1.0patch1"));
+/// assert!(key("1.9") < key("1.10"));
+/// assert_eq!(key("1.0"), key("1.0.0"));
+///
+/// // The 1.2 series as a range scan.
+/// let start = encode_sort_key("1.2", VersionFlags::LOWER_BOUND);
+/// let end = encode_sort_key("1.2", VersionFlags::UPPER_BOUND);
+/// assert!((start.clone()..=end.clone()).contains(&key("1.2.5")));
+/// assert!(!(start..=end).contains(&key("1.3")));
+/// ```
+pub fn encode_sort_key(version: &str, flags: VersionFlags) -> Vec<u8> {
+    // A version is compared as its components followed by padding forever,
+    // and zero components compare equal to zero padding. Each run of zero
+    // components is therefore encoded together with the component that
+    // follows it: how the run length orders depends on whether that
+    // component sorts below zero (a longer run is greater) or above it (a
+    // longer run is smaller). Zero padding ends the encoding between the two
+    // classes, absorbing any trailing zeroes.
+    let mut out = Vec::with_capacity(version.len() + 8);
+    let mut zeroes = 0usize;
+    for component in components(version, flags) {
+        if component.kind == ComponentKind::Zero {
+            zeroes += 1;
+            continue;
+        }
+        push_run(&mut out, zeroes, component.kind);
+        zeroes = 0;
+        match (component.number, component.alpha) {
+            (Some(number), _) => {
+                push_length(&mut out, number.len());
+                out.extend_from_slice(number.as_bytes());
+            }
+            (None, Some(alpha)) => out.push(alpha.as_bytes()[0].to_ascii_lowercase()),
+            (None, None) => {}
+        }
+    }
+
+    match ComponentKind::padding(flags) {
+        ComponentKind::Zero => out.push(ZERO_PADDING),
+        bound => push_run(&mut out, zeroes, bound),
+    }
+    out
+}
+
+/// Markers opening each run in [`encode_sort_key`].
+const BELOW_ZERO: u8 = 0x01;
+const ZERO_PADDING: u8 = 0x02;
+const ABOVE_ZERO: u8 = 0x03;
+
+/// Append a run of `zeroes` zero components followed by a component of
+/// `kind`, whose value the caller appends.
+fn push_run(out: &mut Vec<u8>, zeroes: usize, kind: ComponentKind) {
+    if kind < ComponentKind::Zero {
+        out.push(BELOW_ZERO);
+        push_length(out, zeroes);
+    } else {
+        out.push(ABOVE_ZERO);
+        let start = out.len();
+        push_length(out, zeroes);
+        for byte in &mut out[start..] {
+            *byte = !*byte;
+        }
+    }
+    out.push(kind as u8);
+}
+
+/// Append `len` in an order-preserving, prefix-free form: one byte below
+/// `0xff`, or `0xff` followed by eight big-endian bytes.
+fn push_length(out: &mut Vec<u8>, len: usize) {
+    match u8::try_from(len) {
+        Ok(len) if len < u8::MAX => out.push(len),
+        _ => {
+            out.push(u8::MAX);
+            out.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasher, RandomState};
+
+    use super::*;
+    use crate::pure;
+
+    #[test]
+    fn keys_and_encodings_compare_like_strings() {
+        let versions = [
+            "",
+            "0",
+            "1",
+            "1.0",
+            "1.0.0",
+            "01",
+            "1.0a",
+            "1.0a1",
+            "1.0alpha1",
+            "1.0b",
+            "1.0p1",
+            "1.0patch1",
+            "1.0pl1",
+            "1.0rc1",
+            "1.1",
+            "1.10",
+            "2.0.0.0.1",
+            "1.0\x005",
+            "9999999999999999999",
+            "10000000000000000000",
+            "99999999999999999999",
+            "100000000000000000000",
+            "x",
+            "1.x.2",
+            "1.0.0.0.a",
+            "1.0.0.0.1",
+            "1.alpha",
+            "1.0alpha",
+            "0.0",
+            "a.0.0",
+        ];
+        let flags = [
+            VersionFlags::empty(),
+            VersionFlags::P_IS_PATCH,
+            VersionFlags::ANY_IS_PATCH,
+            VersionFlags::LOWER_BOUND,
+            VersionFlags::UPPER_BOUND,
+        ];
+        let state = RandomState::new();
+        for v1 in versions {
+            for v2 in versions {
+                for f1 in flags {
+                    for f2 in flags {
+                        let (k1, k2) = (SortKey::new(v1, f1), SortKey::new(v2, f2));
+                        let expected = pure::compare_with_flags(v1, v2, f1, f2);
+                        assert_eq!(k1.cmp(&k2), expected, "{v1:?} {f1:?} vs {v2:?} {f2:?}");
+                        if expected == Ordering::Equal {
+                            assert_eq!(state.hash_one(&k1), state.hash_one(&k2));
+                        }
+                        let (e1, e2) = (encode_sort_key(v1, f1), encode_sort_key(v2, f2));
+                        assert_eq!(e1.cmp(&e2), expected, "{v1:?} {f1:?} vs {v2:?} {f2:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn long_runs_and_numbers() {
+        let none = VersionFlags::empty();
+        let many_zeroes = format!("1{}", ".0".repeat(300));
+        let long_number = "9".repeat(300);
+        let cases = [
+            (format!("{many_zeroes}.1"), format!("{many_zeroes}.0.1")),
+            (format!("{many_zeroes}.a"), format!("{many_zeroes}.0.a")),
+            (format!("1.{long_number}"), format!("1.1{long_number}")),
+            (many_zeroes.clone(), "1".to_owned()),
+        ];
+        for (v1, v2) in &cases {
+            assert_eq!(
+                encode_sort_key(v1, none).cmp(&encode_sort_key(v2, none)),
+                pure::compare(v1, v2),
+                "{v1:?} vs {v2:?}",
+            );
+        }
+    }
+}
@@ -3,29 +3,163 @@
 //!
 //! # Raw FFI
 //!
-//! The [`ffi`] module exposes the raw C functions and constants directly.
+//! The `ffi` module exposes the raw C functions and constants directly. It
+//! and the root re-exports of `version_compare2` and `version_compare4` need
+//! the default `bundled` feature. The `VERSIONFLAG_*` constants are always
+//! available.
 //!
 //! # Safe API
 //!
 //! [`compare`] and [`compare_with_flags`] provide safe Rust wrappers that return
-//! [`std::cmp::Ordering`].
+//! [`std::cmp::Ordering`]; flags are passed as [`VersionFlags`]. They panic on
+//! version strings containing an interior null byte; [`try_compare`] and
+//! [`try_compare_with_flags`] report a [`NulError`] instead. Versions shorter
+//! than 256 bytes are passed to C through a stack buffer, so comparisons do
+//! not allocate.
+//!
+//! [`Version`] is an owned version string, optionally carrying its own
+//! [`VersionFlags`], whose [`Ord`], [`Eq`] and [`Hash`] follow libversion, so
+//! it can be sorted and used as a map key directly.
+//! [`VersionStr`] and [`VersionBuf`] are the borrowed and owned pair for working
+//! with versions inside existing buffers, like [`Path`](std::path::Path) and
+//! [`PathBuf`](std::path::PathBuf).
+//!
+//! [`VersionRange`] is a range of versions with inclusive or exclusive ends;
+//! [`VersionRange::prefix`] matches a whole release series such as `1.2.*`
+//! through the bound flags. [`VersionReq`] parses requirement strings such as
+//! `>=1.0, <2.0 || =3.1` or `1.2.*`.
+//!
+//! [`sort_versions`], [`max_version`], [`latest_matching`] and related
+//! functions cover the usual operations on collections of version strings.
+//! [`SortKey`] parses a version once for repeated comparisons; the sort
+//! functions use it so that each version is parsed only once.
+//! [`encode_sort_key`] turns a version into bytes that sort in libversion's
+//! order, for use as a database or key-value store key.
+//!
+//! [`normalize`] spells a version canonically, so that equal versions are
+//! stored and displayed the same way. [`classify`] tells releases,
+//! pre-releases and post-releases apart, consistently with how they compare;
+//! [`is_prerelease`] and [`is_stable`] are shorthands for filtering.
+//!
+//! [`components`] shows how libversion splits a version and ranks each part,
+//! and [`explain`] traces a comparison component by component, naming the
+//! [`Rule`] that decided it.
+//!
+//! [`libversion_version`] and the `LIBVERSION_VERSION*` constants report the
+//! version of libversion in use. The build script also passes it, with the
+//! location of the C headers, to dependent build scripts as
+//! `DEP_VERSION_VERSION`, `DEP_VERSION_INCLUDE` and `DEP_VERSION_ROOT`.
+//!
+//! # Features
+//!
+//! - `bundled` (default): compare with the C library, built from the bundled
+//!   copy, and provide `ffi`, `version_compare2` and `version_compare4`.
+//!   Without it, all comparisons go through [`pure`], a safe Rust port of
+//!   libversion, and no C compiler is needed; results are identical.
+//! - `serde`: `Serialize` and `Deserialize` for [`Version`], [`VersionStr`],
+//!   [`VersionBuf`] and [`VersionFlags`], and the `serde::sorted` helper for
+//!   keeping a list of version strings in order; see the `serde` module.
+//! - `system`: link a system-installed libversion found with pkg-config
+//!   instead of building the bundled copy, falling back to the bundled copy
+//!   if none is found. Implies `bundled`.
+//! - `bindgen`: generate the `ffi` bindings from the C headers at build
+//!   time instead of using the pre-generated ones. Needs libclang. Implies
+//!   `bundled`.
 
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
 /// Raw FFI bindings generated by bindgen.
+///
+/// These cover everything libversion's public headers declare, except the
+/// `LIBVERSION_VERSION*` macros from `config.h`: use [`LIBVERSION_VERSION`]
+/// and friends, which report the library actually linked. Each item has a
+/// safe counterpart:
+///
+/// | C | Safe API |
+/// |---|----------|
+/// | `version_compare2` | [`compare`], [`try_compare`] |
+/// | `version_compare4` | [`compare_with_flags`], [`try_compare_with_flags`] |
+/// | `VERSIONFLAG_*` | [`VersionFlags`] |
+///
+/// These are pre-generated in `generated/bindings.rs`, so building the crate
+/// does not need libclang. With the `bindgen` feature they are generated from
+/// the C headers at build time instead.
+#[cfg(feature = "bundled")]
 pub mod ffi {
+    #[cfg(feature = "bindgen")]
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+    #[cfg(not(feature = "bindgen"))]
+    include!("../generated/bindings.rs");
 }
 
+pub mod pure;
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "bundled")]
+mod c_str;
+mod classify;
+mod error;
+mod explain;
+mod flags;
+mod info;
+mod key;
+mod normalize;
+mod parse;
+mod range;
+mod req;
+mod sort;
+mod version;
+
+pub use classify::{Classification, classify, is_prerelease, is_stable};
+pub use error::{Argument, NulError, ParseReqError, ReqErrorKind};
+pub use explain::{Explanation, Rule, Step, explain};
+pub use flags::VersionFlags;
+pub use info::{
+    LIBVERSION_VERSION, LIBVERSION_VERSION_MAJOR, LIBVERSION_VERSION_MINOR,
+    LIBVERSION_VERSION_PATCH, libversion_version,
+};
+pub use key::{SortKey, encode_sort_key};
+pub use normalize::normalize;
+pub use parse::{Component, ComponentKind, Components, components};
+pub use range::VersionRange;
+pub use req::{Op, Predicate, VersionReq};
+pub use sort::{
+    dedup_equal_versions, latest_matching, max_version, min_version, sort_versions,
+    sort_versions_by_key,
+};
+pub use version::{Version, VersionBuf, VersionStr};
+
+#[cfg(feature = "bundled")]
 pub use ffi::{
     VERSIONFLAG_ANY_IS_PATCH, VERSIONFLAG_LOWER_BOUND, VERSIONFLAG_P_IS_PATCH,
     VERSIONFLAG_UPPER_BOUND, version_compare2, version_compare4,
 };
 
+// Without the C library, define the raw flag constants from `VersionFlags`,
+// so that code using them builds with or without `bundled`.
+
+/// `VersionFlags::P_IS_PATCH` as libversion's C constant.
+#[cfg(not(feature = "bundled"))]
+pub const VERSIONFLAG_P_IS_PATCH: u32 = VersionFlags::P_IS_PATCH.bits();
+/// `VersionFlags::ANY_IS_PATCH` as libversion's C constant.
+#[cfg(not(feature = "bundled"))]
+pub const VERSIONFLAG_ANY_IS_PATCH: u32 = VersionFlags::ANY_IS_PATCH.bits();
+/// `VersionFlags::LOWER_BOUND` as libversion's C constant.
+#[cfg(not(feature = "bundled"))]
+pub const VERSIONFLAG_LOWER_BOUND: u32 = VersionFlags::LOWER_BOUND.bits();
+/// `VersionFlags::UPPER_BOUND` as libversion's C constant.
+#[cfg(not(feature = "bundled"))]
+pub const VERSIONFLAG_UPPER_BOUND: u32 = VersionFlags::UPPER_BOUND.bits();
+
 use std::cmp::Ordering;
-use std::ffi::CString;
+#[cfg(feature = "bundled")]
+use std::os::raw::c_int;
+
+#[cfg(feature = "bundled")]
+use c_str::with_c_str;
 
 /// Compare two version strings.
 ///
@@ -33,7 +167,9 @@ use std::ffi::CString;
 ///
 /// # Panics
 ///
-/// Panics if either version string contains an interior null byte.
+/// Panics if either version string contains an interior null byte, with the
+/// message of the corresponding [`NulError`]. Use [`try_compare`] for input
+/// that is not known to be free of null bytes.
 ///
 /// # Examples
 ///
@@ -43,40 +179,113 @@ use std::ffi::CString;
 /// assert_eq!(libversion_sys::compare("1.0", "1.0.0"), Ordering::Equal);
 /// ```
 pub fn compare(v1: &str, v2: &str) -> Ordering {
-    let v1 = CString::new(v1).expect("v1 contains interior null byte");
-    let v2 = CString::new(v2).expect("v2 contains interior null byte");
-    let result = unsafe { ffi::version_compare2(v1.as_ptr(), v2.as_ptr()) };
-    result.cmp(&0)
+    try_compare(v1, v2).unwrap_or_else(|e| panic!("{e}"))
 }
 
 /// Compare two version strings with per-version flags.
 ///
-/// See [`VERSIONFLAG_P_IS_PATCH`], [`VERSIONFLAG_ANY_IS_PATCH`],
-/// [`VERSIONFLAG_LOWER_BOUND`], [`VERSIONFLAG_UPPER_BOUND`].
+/// See [`VersionFlags`] for the available flags.
 ///
 /// # Panics
 ///
-/// Panics if either version string contains an interior null byte.
+/// Panics if either version string contains an interior null byte, with the
+/// message of the corresponding [`NulError`]. Use [`try_compare_with_flags`]
+/// for input that is not known to be free of null bytes.
 ///
 /// # Examples
 ///
 /// ```
 /// use std::cmp::Ordering;
-/// use libversion_sys::VERSIONFLAG_P_IS_PATCH;
+/// use libversion_sys::VersionFlags;
 ///
 /// // By default "p" means "pre", but with the flag it means "patch" (post-release)
 /// assert_eq!(
-///     libversion_sys::compare_with_flags("1.0p1", "1.0post1", VERSIONFLAG_P_IS_PATCH, 0),
+///     libversion_sys::compare_with_flags(
+///         "1.0p1",
+///         "1.0post1",
+///         VersionFlags::P_IS_PATCH,
+///         VersionFlags::empty(),
+///     ),
 ///     Ordering::Equal,
 /// );
 /// ```
-pub fn compare_with_flags(v1: &str, v2: &str, v1_flags: u32, v2_flags: u32) -> Ordering {
-    let v1 = CString::new(v1).expect("v1 contains interior null byte");
-    let v2 = CString::new(v2).expect("v2 contains interior null byte");
-    let result = unsafe {
-        ffi::version_compare4(v1.as_ptr(), v2.as_ptr(), v1_flags as i32, v2_flags as i32)
-    };
-    result.cmp(&0)
+pub fn compare_with_flags(
+    v1: &str,
+    v2: &str,
+    v1_flags: VersionFlags,
+    v2_flags: VersionFlags,
+) -> Ordering {
+    try_compare_with_flags(v1, v2, v1_flags, v2_flags).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Compare two version strings, returning an error instead of panicking if
+/// either contains an interior null byte.
+///
+/// # Errors
+///
+/// Returns a [`NulError`] naming the offending argument and the offset of its
+/// first null byte. `v1` is checked before `v2`.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use libversion_sys::{Argument, try_compare};
+///
+/// assert_eq!(try_compare("1.0", "1.1"), Ok(Ordering::Less));
+/// assert_eq!(try_compare("1.0\0", "1.1").unwrap_err().argument(), Argument::V1);
+/// ```
+pub fn try_compare(v1: &str, v2: &str) -> Result<Ordering, NulError> {
+    #[cfg(feature = "bundled")]
+    {
+        let result = with_c_str(v1, Argument::V1, |v1| {
+            with_c_str(v2, Argument::V2, |v2| unsafe {
+                ffi::version_compare2(v1.as_ptr(), v2.as_ptr())
+            })
+        })??;
+        Ok(result.cmp(&0))
+    }
+    #[cfg(not(feature = "bundled"))]
+    {
+        NulError::check(v1, Argument::V1)?;
+        NulError::check(v2, Argument::V2)?;
+        Ok(pure::compare(v1, v2))
+    }
+}
+
+/// Compare two version strings with per-version flags, returning an error
+/// instead of panicking if either contains an interior null byte.
+///
+/// # Errors
+///
+/// Returns a [`NulError`] naming the offending argument and the offset of its
+/// first null byte. `v1` is checked before `v2`.
+pub fn try_compare_with_flags(
+    v1: &str,
+    v2: &str,
+    v1_flags: VersionFlags,
+    v2_flags: VersionFlags,
+) -> Result<Ordering, NulError> {
+    #[cfg(feature = "bundled")]
+    {
+        let result = with_c_str(v1, Argument::V1, |v1| {
+            with_c_str(v2, Argument::V2, |v2| unsafe {
+                ffi::version_compare4(
+                    v1.as_ptr(),
+                    v2.as_ptr(),
+                    v1_flags.bits() as c_int,
+                    v2_flags.bits() as c_int,
+                )
+            })
+        })??;
+        Ok(result.cmp(&0))
+    }
+    #[cfg(not(feature = "bundled"))]
+    {
+        NulError::check(v1, Argument::V1)?;
+        NulError::check(v2, Argument::V2)?;
+        Ok(pure::compare_with_flags(v1, v2, v1_flags, v2_flags))
+    }
 }
 
 #[cfg(test)]
@@ -110,13 +319,169 @@ mod tests {
 
         // With flag: p == patch (post-release)
         assert_eq!(
-            compare_with_flags("1.0p1", "1.0", VERSIONFLAG_P_IS_PATCH, 0),
+            compare_with_flags(
+                "1.0p1",
+                "1.0",
+                VersionFlags::P_IS_PATCH,
+                VersionFlags::empty()
+            ),
             Ordering::Greater,
         );
     }
 
     #[test]
+    fn version_ord() {
+        let mut versions: Vec<Version> = ["1.10", "1.0", "1.2rc1", "1.2"]
+            .into_iter()
+            .map(Version::from)
+            .collect();
+        versions.sort();
+        let sorted: Vec<&str> = versions.iter().map(Version::as_str).collect();
+        assert_eq!(sorted, ["1.0", "1.2rc1", "1.2", "1.10"]);
+        assert_eq!(versions.iter().max().unwrap().as_str(), "1.10");
+    }
+
+    #[test]
+    fn version_eq_and_hash() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let mut hashed = HashMap::new();
+        let mut ordered = BTreeMap::new();
+        for v in ["1.0", "1.0.0", "1.00", "1_0", "1.0a", "1.0alpha", "1.0.1"] {
+            *hashed.entry(Version::from(v)).or_insert(0) += 1;
+            *ordered.entry(Version::from(v)).or_insert(0) += 1;
+        }
+
+        assert_eq!(hashed[&Version::from("1")], 4);
+        assert_eq!(ordered[&Version::from("1")], 4);
+        assert_eq!(hashed[&Version::from("1.0A")], 1);
+        assert_eq!(hashed[&Version::from("1.0a0")], 1);
+        assert_eq!(hashed.len(), ordered.len());
+    }
+
+    #[test]
+    fn version_with_flags() {
+        use std::collections::HashSet;
+
+        let p_is_patch = |v| Version::with_flags(v, VersionFlags::P_IS_PATCH);
+        let mut openssh = [p_is_patch("9.6p1"), p_is_patch("9.6"), p_is_patch("9.5p2")];
+        openssh.sort();
+        let sorted: Vec<&str> = openssh.iter().map(Version::as_str).collect();
+        assert_eq!(sorted, ["9.5p2", "9.6", "9.6p1"]);
+        assert_eq!(openssh[0].flags(), VersionFlags::P_IS_PATCH);
+
+        // Each side is interpreted with its own flags.
+        assert_eq!(p_is_patch("1.0p1"), Version::new("1.0post1"));
+        assert!(Version::new("1.0p1") < p_is_patch("1.0"));
+
+        let lower = Version::with_flags("1.0", VersionFlags::LOWER_BOUND);
+        let upper = Version::with_flags("1.0", VersionFlags::UPPER_BOUND);
+        assert!(lower < Version::new("1.0alpha1"));
+        assert!(upper > Version::new("1.0.1"));
+        assert!(upper < Version::new("1.1"));
+
+        let set: HashSet<Version> = [
+            p_is_patch("1.0p1"),
+            Version::new("1.0post1"),
+            Version::new("1.0"),
+            lower.clone(),
+            Version::with_flags("1.0.0", VersionFlags::LOWER_BOUND),
+            upper.clone(),
+        ]
+        .into();
+        assert_eq!(set.len(), 5);
+        assert!(set.contains(&Version::with_flags("1", VersionFlags::ANY_IS_PATCH)));
+        assert!(!set.contains(&Version::with_flags("1", VersionFlags::LOWER_BOUND)));
+    }
+
+    #[test]
+    fn version_str_borrowed_lookup() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let buffer = String::from("1.0.0 2.0rc1 2.0");
+        let borrowed: Vec<&VersionStr> = buffer.split(' ').map(VersionStr::new).collect();
+        assert!(borrowed[0] < borrowed[1]);
+        assert!(borrowed[1] < borrowed[2]);
+        assert_eq!(borrowed[0], VersionStr::new("1"));
+
+        let hashed: HashSet<VersionBuf> = borrowed.iter().map(|v| (*v).to_owned()).collect();
+        let ordered: BTreeSet<VersionBuf> = hashed.iter().cloned().collect();
+        assert!(hashed.contains(VersionStr::new("2.0.0")));
+        assert!(ordered.contains(VersionStr::new("1")));
+        assert!(!hashed.contains(VersionStr::new("2.0a")));
+        assert_eq!(ordered.first().unwrap().as_str(), "1.0.0");
+    }
+
+    #[test]
+    fn try_compare_reports_nul() {
+        assert_eq!(try_compare("1.0", "1.0.0"), Ok(Ordering::Equal));
+        assert_eq!(
+            try_compare_with_flags(
+                "1.0p1",
+                "1.0",
+                VersionFlags::P_IS_PATCH,
+                VersionFlags::empty()
+            ),
+            Ok(Ordering::Greater),
+        );
+
+        let err = try_compare("1.0\0beta", "1.0").unwrap_err();
+        assert_eq!(err, NulError::new(Argument::V1, 3));
+
+        let err = try_compare_with_flags("1.0", "\0", VersionFlags::empty(), VersionFlags::empty())
+            .unwrap_err();
+        assert_eq!(err.argument(), Argument::V2);
+        assert_eq!(err.nul_position(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "v2 contains an interior null byte at position 1")]
+    fn compare_panics_on_nul() {
+        compare("1.0", "1\0");
+    }
+
+    #[test]
+    fn long_versions() {
+        // Longer than the stack buffer, so the heap fallback is used.
+        let long = format!("1{}", ".0".repeat(200));
+        let longer = format!("{long}.1");
+        assert_eq!(compare(&long, "1"), Ordering::Equal);
+        assert_eq!(compare(&long, &longer), Ordering::Less);
+        assert_eq!(
+            try_compare("1", &format!("{long}\0")).unwrap_err(),
+            NulError::new(Argument::V2, 401),
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bindgen")]
+    fn committed_bindings_are_current() {
+        assert!(
+            include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"))
+                == include_str!("../generated/bindings.rs"),
+            "generated/bindings.rs is out of date; run `bash scripts/generate-bindings.sh`",
+        );
+    }
+
+    #[test]
+    fn flag_values_match_c() {
+        // The values from libversion/version.h, so that the constants
+        // defined in Rust without `bundled` are checked too.
+        assert_eq!(VERSIONFLAG_P_IS_PATCH, 0x1);
+        assert_eq!(VERSIONFLAG_ANY_IS_PATCH, 0x2);
+        assert_eq!(VERSIONFLAG_LOWER_BOUND, 0x4);
+        assert_eq!(VERSIONFLAG_UPPER_BOUND, 0x8);
+        assert_eq!(VersionFlags::P_IS_PATCH.bits(), VERSIONFLAG_P_IS_PATCH);
+        assert_eq!(VersionFlags::ANY_IS_PATCH.bits(), VERSIONFLAG_ANY_IS_PATCH);
+        assert_eq!(VersionFlags::LOWER_BOUND.bits(), VERSIONFLAG_LOWER_BOUND);
+        assert_eq!(VersionFlags::UPPER_BOUND.bits(), VERSIONFLAG_UPPER_BOUND);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled")]
     fn ffi_direct() {
+        use std::ffi::CString;
+
         let v1 = CString::new("1.0").unwrap();
         let v2 = CString::new("2.0").unwrap();
         let result = unsafe { ffi::version_compare2(v1.as_ptr(), v2.as_ptr()) };
@@ -0,0 +1,166 @@
+use crate::VersionFlags;
+use crate::parse::{ComponentKind, components};
+
+/// The canonical spelling of an alphabetic component, from its kind and
+/// first letter: the matching keyword where there is one, otherwise the
+/// lowercased letter on its own.
+fn canonical_word(kind: ComponentKind, letter: u8, flags: VersionFlags) -> &'static str {
+    const LETTERS: [&str; 26] = [
+        "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r",
+        "s", "t", "u", "v", "w", "x", "y", "z",
+    ];
+    match (kind, letter) {
+        (ComponentKind::PreRelease, b'a') => "alpha",
+        (ComponentKind::PreRelease, b'b') => "beta",
+        (ComponentKind::PreRelease, b'r') => "rc",
+        (ComponentKind::PreRelease, b'p') => "pre",
+        (ComponentKind::PostRelease, b'p') => "post",
+        (ComponentKind::PostRelease, b'e') => "errata",
+        // A lone `p` would be read back as a post-release keyword.
+        (ComponentKind::LetterSuffix, b'p') if flags.contains(VersionFlags::P_IS_PATCH) => "pp",
+        _ => LETTERS[usize::from(letter - b'a')],
+    }
+}
+
+/// Produce the canonical spelling of `version`, interpreted with `flags`.
+///
+/// The result compares equal to `version` when interpreted with the same
+/// flags, and versions that compare equal normalize to the same string.
+/// Numbers lose their leading zeroes, components are separated by `.`,
+/// trailing zero components are dropped (unless a bound flag makes them
+/// significant), and letter runs are lowercased and, since libversion only
+/// looks at their first letter, spelled as the keyword they stand for:
+/// `a`, `alpha` and `ALPHA` all become `alpha`, and `patch`, `pl` and `post`
+/// become `post`. Keywords are attached to the number before them, as in
+/// `1.0alpha1`.
+///
+/// Like the C library, parsing stops at the first null byte.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, normalize};
+///
+/// let none = VersionFlags::empty();
+/// for version in ["1.0", "1.0.0", "1_0", "1.00", "01"] {
+///     assert_eq!(normalize(version, none), "1");
+/// }
+/// assert_eq!(normalize("1.0-RC.1", none), "1.0rc1");
+/// assert_eq!(normalize("V2.1.0A", none), "v2.1.0a");
+/// assert_eq!(normalize("1.0pl2", none), "1.0post2");
+/// assert_eq!(normalize("1.0p2", none), "1.0pre2");
+/// assert_eq!(normalize("1.0p2", VersionFlags::P_IS_PATCH), "1.0post2");
+/// ```
+pub fn normalize(version: &str, flags: VersionFlags) -> String {
+    let mut parts: Vec<_> = components(version, flags).collect();
+    if ComponentKind::padding(flags) == ComponentKind::Zero {
+        while parts.last().is_some_and(|c| c.kind == ComponentKind::Zero) {
+            parts.pop();
+        }
+        if parts.is_empty() {
+            return "0".to_owned();
+        }
+    }
+
+    let mut out = String::with_capacity(version.len());
+    let mut previous = None;
+    for component in parts {
+        let (text, is_number) = match (component.number, component.alpha) {
+            (Some(number), _) => (number, true),
+            (None, Some(alpha)) => (
+                canonical_word(
+                    component.kind,
+                    alpha.as_bytes()[0].to_ascii_lowercase(),
+                    flags,
+                ),
+                false,
+            ),
+            (None, None) => continue,
+        };
+        // Attach a component to the one before it only where parsing it back
+        // gives the same result: a letter suffix must follow its number, a
+        // keyword may, and a number may follow anything but a letter suffix.
+        let attach = match previous {
+            None => true,
+            Some(ComponentKind::Zero | ComponentKind::NonZero) => {
+                component.kind == ComponentKind::LetterSuffix || (!is_number && text.len() > 1)
+            }
+            Some(ComponentKind::LetterSuffix) => false,
+            Some(_) => is_number,
+        };
+        if !attach {
+            out.push('.');
+        }
+        out.push_str(text);
+        previous = Some(component.kind);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pure;
+
+    #[test]
+    fn normalized_versions_compare_equal() {
+        let versions = [
+            "",
+            "0",
+            "0.0",
+            "1",
+            "1.0",
+            "01.002",
+            "1.0a",
+            "1.0A.1",
+            "1.0a1",
+            "1.0alpha1",
+            "1.0.x.2",
+            "1.0x",
+            "1.0pl1",
+            "1.0errata",
+            "1.0.p",
+            "1.0p",
+            "0pz",
+            "1.0.rc.1",
+            "alpha",
+            "1a.b2",
+            "1.0\x001",
+            "...",
+            "v1.2-SNAPSHOT",
+        ];
+        let flags = [
+            VersionFlags::empty(),
+            VersionFlags::P_IS_PATCH,
+            VersionFlags::ANY_IS_PATCH,
+            VersionFlags::LOWER_BOUND,
+            VersionFlags::UPPER_BOUND,
+        ];
+        for version in versions {
+            for flags in flags {
+                let normalized = normalize(version, flags);
+                assert_eq!(
+                    pure::compare_with_flags(version, &normalized, flags, flags),
+                    std::cmp::Ordering::Equal,
+                    "{version:?} normalized to {normalized:?} with {flags:?}",
+                );
+                assert_eq!(normalize(&normalized, flags), normalized);
+            }
+        }
+    }
+
+    #[test]
+    fn spelling() {
+        let none = VersionFlags::empty();
+        assert_eq!(normalize("", none), "0");
+        assert_eq!(normalize("", VersionFlags::LOWER_BOUND), "");
+        assert_eq!(normalize("1.0", VersionFlags::UPPER_BOUND), "1.0");
+        assert_eq!(normalize("1.0A.1", none), "1.0a.1");
+        assert_eq!(normalize("1.0.X.2", none), "1.0.x2");
+        assert_eq!(normalize("1.0x2", VersionFlags::ANY_IS_PATCH), "1.0.x2");
+        assert_eq!(normalize("1.0-SNAPSHOT", none), "1.0.s");
+        assert_eq!(normalize("1.0.Beta", none), "1.0beta");
+        assert_eq!(normalize("1pz", none), "1p");
+        assert_eq!(normalize("1pz", VersionFlags::P_IS_PATCH), "1pp");
+    }
+}
@@ -0,0 +1,350 @@
+//! Rust mirror of the tokenizer in `libversion/private/parse.c`.
+//!
+//! libversion never exposes its parsed form. This module reproduces its
+//! component split and metaorder assignment exactly, both for [`components`]
+//! and for [`Hash`] implementations that must agree with libversion's
+//! comparison: equal versions produce equal component sequences (modulo
+//! trailing padding).
+
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+
+use crate::VersionFlags;
+
+/// Rank of a version component, mirroring `METAORDER_*` in libversion.
+///
+/// Components are compared by kind first, in declaration order: a
+/// pre-release keyword sorts before zero, which sorts before a post-release
+/// keyword, and so on. Only components of the same kind are compared by value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ComponentKind {
+    /// Padding of a version compared with
+    /// [`LOWER_BOUND`](VersionFlags::LOWER_BOUND); sorts before everything.
+    LowerBound,
+    /// A pre-release keyword such as `alpha`, `beta`, `rc` or `pre`, or an
+    /// unknown letter run.
+    PreRelease,
+    /// A number that is zero, including the implicit padding of a version.
+    Zero,
+    /// A post-release keyword such as `patch`, `post` or `pl`.
+    PostRelease,
+    /// A non-zero number.
+    NonZero,
+    /// A letter directly following a number and not followed by one, as in
+    /// `1.0a`.
+    LetterSuffix,
+    /// Padding of a version compared with
+    /// [`UPPER_BOUND`](VersionFlags::UPPER_BOUND); sorts after everything.
+    UpperBound,
+}
+
+impl ComponentKind {
+    /// The kind of the implicit components that pad a version interpreted
+    /// with `flags` past its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use libversion_sys::{ComponentKind, VersionFlags};
+    ///
+    /// assert_eq!(ComponentKind::padding(VersionFlags::empty()), ComponentKind::Zero);
+    /// assert_eq!(
+    ///     ComponentKind::padding(VersionFlags::UPPER_BOUND),
+    ///     ComponentKind::UpperBound,
+    /// );
+    /// ```
+    pub fn padding(flags: VersionFlags) -> Self {
+        if flags.contains(VersionFlags::LOWER_BOUND) {
+            ComponentKind::LowerBound
+        } else if flags.contains(VersionFlags::UPPER_BOUND) {
+            ComponentKind::UpperBound
+        } else {
+            ComponentKind::Zero
+        }
+    }
+}
+
+/// A single component of a version string, as parsed by libversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Component<'a> {
+    /// The rank of the component.
+    pub kind: ComponentKind,
+    /// For numeric components, the digits without leading zeroes (`"0"` for
+    /// zero).
+    pub number: Option<&'a str>,
+    /// For alphabetic components, the letter run as written. libversion only
+    /// compares its first letter, case-insensitively.
+    pub alpha: Option<&'a str>,
+}
+
+impl Component<'_> {
+    /// The implicit component that pads a version interpreted with `flags`
+    /// past its end.
+    pub(crate) fn padding(flags: VersionFlags) -> Component<'static> {
+        let kind = ComponentKind::padding(flags);
+        Component {
+            kind,
+            number: (kind == ComponentKind::Zero).then_some("0"),
+            alpha: None,
+        }
+    }
+
+    /// Feed the parts of the component that libversion's comparison looks
+    /// at into `state`: the kind, then the lowercased first letter for
+    /// alphabetic components or the significant digits for numeric ones.
+    pub(crate) fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.kind.hash(state);
+        if let Some(alpha) = self.alpha {
+            alpha.as_bytes()[0].to_ascii_lowercase().hash(state);
+        }
+        self.number.hash(state);
+    }
+}
+
+enum Keyword {
+    Unknown,
+    PreRelease,
+    PostRelease,
+}
+
+fn classify_keyword(word: &str, flags: VersionFlags) -> Keyword {
+    let starts_with = |prefix: &str| {
+        word.len() >= prefix.len()
+            && word.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+    };
+    let is = |keyword: &str| word.eq_ignore_ascii_case(keyword);
+
+    if is("alpha") || is("beta") || is("rc") || starts_with("pre") {
+        Keyword::PreRelease
+    } else if starts_with("post")
+        || starts_with("patch")
+        || is("pl")
+        || is("errata")
+        || (flags.contains(VersionFlags::P_IS_PATCH) && is("p"))
+    {
+        Keyword::PostRelease
+    } else {
+        Keyword::Unknown
+    }
+}
+
+/// Split a version string into the components libversion compares.
+///
+/// Separators are skipped, numbers lose their leading zeroes, and each letter
+/// run is ranked as a pre-release keyword, post-release keyword or letter
+/// suffix according to `flags`. Like the C implementation, parsing stops at
+/// the first null byte. The implicit padding past the end of the version,
+/// of kind [`ComponentKind::padding`], is not yielded.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{ComponentKind, VersionFlags, components};
+///
+/// let kinds: Vec<ComponentKind> = components("1.0a_rc2", VersionFlags::empty())
+///     .map(|c| c.kind)
+///     .collect();
+/// assert_eq!(
+///     kinds,
+///     [
+///         ComponentKind::NonZero,
+///         ComponentKind::Zero,
+///         ComponentKind::LetterSuffix,
+///         ComponentKind::PreRelease,
+///         ComponentKind::NonZero,
+///     ],
+/// );
+///
+/// let p = components("1p1", VersionFlags::P_IS_PATCH).nth(1).unwrap();
+/// assert_eq!(p.kind, ComponentKind::PostRelease);
+/// assert_eq!(p.alpha, Some("p"));
+/// ```
+pub fn components(version: &str, flags: VersionFlags) -> Components<'_> {
+    let end = version.find('\0').unwrap_or(version.len());
+    Components {
+        rest: &version[..end],
+        flags,
+        pending: None,
+    }
+}
+
+/// Iterator over the components of a version string, created by
+/// [`components`].
+#[derive(Debug, Clone)]
+pub struct Components<'a> {
+    rest: &'a str,
+    flags: VersionFlags,
+    pending: Option<Component<'a>>,
+}
+
+impl<'a> Components<'a> {
+    fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> &'a str {
+        let len = self
+            .rest
+            .bytes()
+            .position(|c| !pred(c))
+            .unwrap_or(self.rest.len());
+        let (head, tail) = self.rest.split_at(len);
+        self.rest = tail;
+        head
+    }
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = Component<'a>;
+
+    fn next(&mut self) -> Option<Component<'a>> {
+        if let Some(component) = self.pending.take() {
+            return Some(component);
+        }
+
+        self.take_while(|c| !c.is_ascii_alphanumeric());
+        let first = *self.rest.as_bytes().first()?;
+
+        let component = if first.is_ascii_alphabetic() {
+            let alpha = self.take_while(|c| c.is_ascii_alphabetic());
+            let kind = match classify_keyword(alpha, self.flags) {
+                Keyword::Unknown if self.flags.contains(VersionFlags::ANY_IS_PATCH) => {
+                    ComponentKind::PostRelease
+                }
+                Keyword::Unknown | Keyword::PreRelease => ComponentKind::PreRelease,
+                Keyword::PostRelease => ComponentKind::PostRelease,
+            };
+            Component {
+                kind,
+                number: None,
+                alpha: Some(alpha),
+            }
+        } else {
+            self.take_while(|c| c == b'0');
+            let digits = self.take_while(|c| c.is_ascii_digit());
+            let (kind, number) = if digits.is_empty() {
+                (ComponentKind::Zero, "0")
+            } else {
+                (ComponentKind::NonZero, digits)
+            };
+
+            // A letter run glued to a number and not followed by another
+            // number (`1a`, `1a.2`, but not `1a2`) is a letter suffix unless
+            // it is a known keyword.
+            let word_len = self
+                .rest
+                .bytes()
+                .position(|c| !c.is_ascii_alphabetic())
+                .unwrap_or(self.rest.len());
+            let followed_by_digit = self
+                .rest
+                .as_bytes()
+                .get(word_len)
+                .is_some_and(u8::is_ascii_digit);
+            if word_len > 0 && !followed_by_digit {
+                let word = self.take_while(|c| c.is_ascii_alphabetic());
+                let kind = match classify_keyword(word, self.flags) {
+                    Keyword::Unknown => ComponentKind::LetterSuffix,
+                    Keyword::PreRelease => ComponentKind::PreRelease,
+                    Keyword::PostRelease => ComponentKind::PostRelease,
+                };
+                self.pending = Some(Component {
+                    kind,
+                    number: None,
+                    alpha: Some(word),
+                });
+            }
+
+            Component {
+                kind,
+                number: Some(number),
+                alpha: None,
+            }
+        };
+
+        Some(component)
+    }
+}
+
+impl FusedIterator for Components<'_> {}
+
+/// Hash `version` so that versions libversion considers equal hash equally.
+///
+/// Trailing components equal to the implicit padding are only hashed once
+/// something else follows them. The padding itself is hashed last, since a
+/// bound never equals an unbounded version.
+pub(crate) fn hash_version<H: Hasher>(version: &str, flags: VersionFlags, state: &mut H) {
+    let padding = ComponentKind::padding(flags);
+    let mut zeroes = 0usize;
+    for component in components(version, flags) {
+        if component.kind == padding {
+            zeroes += 1;
+            continue;
+        }
+        for _ in 0..zeroes {
+            ComponentKind::Zero.hash(state);
+        }
+        zeroes = 0;
+        component.hash_key(state);
+    }
+    padding.hash(state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(version: &str, flags: VersionFlags) -> Vec<(ComponentKind, &str)> {
+        components(version, flags)
+            .map(|c| (c.kind, c.number.or(c.alpha).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn components_match_parse_c() {
+        use ComponentKind::*;
+
+        let none = VersionFlags::empty();
+        assert_eq!(split("1.0", none), [(NonZero, "1"), (Zero, "0")]);
+        assert_eq!(split("007_1", none), [(NonZero, "7"), (NonZero, "1")]);
+        assert_eq!(
+            split("1.0a", none),
+            [(NonZero, "1"), (Zero, "0"), (LetterSuffix, "a")]
+        );
+        assert_eq!(
+            split("1.0a1", none),
+            [
+                (NonZero, "1"),
+                (Zero, "0"),
+                (PreRelease, "a"),
+                (NonZero, "1")
+            ]
+        );
+        assert_eq!(
+            split("1.0patch1", none),
+            [
+                (NonZero, "1"),
+                (Zero, "0"),
+                (PostRelease, "patch"),
+                (NonZero, "1")
+            ]
+        );
+        assert_eq!(
+            split("1.0beta", none),
+            [(NonZero, "1"), (Zero, "0"), (PreRelease, "beta")]
+        );
+        assert_eq!(split("1.0\x001.1", none), [(NonZero, "1"), (Zero, "0")]);
+        assert_eq!(split("...", none), []);
+
+        assert_eq!(split("1p1", none)[1], (PreRelease, "p"));
+        assert_eq!(
+            split("1p1", VersionFlags::P_IS_PATCH)[1],
+            (PostRelease, "p")
+        );
+        assert_eq!(split("1.x", none)[1], (PreRelease, "x"));
+        assert_eq!(
+            split("1.x", VersionFlags::ANY_IS_PATCH)[1],
+            (PostRelease, "x")
+        );
+        assert_eq!(
+            split("1x", VersionFlags::ANY_IS_PATCH)[1],
+            (LetterSuffix, "x")
+        );
+    }
+}
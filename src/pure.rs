@@ -0,0 +1,130 @@
+//! Safe Rust port of libversion's comparison algorithm.
+//!
+//! This follows `libversion/private/parse.c` and `libversion/private/compare.c`
+//! and gives the same result as `version_compare4` on every input, including
+//! stopping at the first null byte. Without the default `bundled` feature,
+//! the crate-level comparison functions route here and the C library is not
+//! built at all; with it, this module is still available, e.g. for checking
+//! the two implementations against each other.
+
+use std::cmp::Ordering;
+
+use crate::VersionFlags;
+use crate::parse::{Component, components};
+
+/// Compare two components the way `compare_components` in libversion does:
+/// by kind, then by the first letter (case-insensitively) for alphabetic
+/// components or numerically for numeric ones.
+pub(crate) fn compare_components(c1: &Component<'_>, c2: &Component<'_>) -> Ordering {
+    c1.kind
+        .cmp(&c2.kind)
+        .then_with(|| match (c1.alpha, c2.alpha) {
+            (Some(a1), Some(a2)) => {
+                let first = |alpha: &str| alpha.as_bytes()[0].to_ascii_lowercase();
+                first(a1).cmp(&first(a2))
+            }
+            _ => {
+                // Leading zeroes are already stripped, so a longer number is larger.
+                let (n1, n2) = (c1.number.unwrap_or(""), c2.number.unwrap_or(""));
+                n1.len().cmp(&n2.len()).then_with(|| n1.cmp(n2))
+            }
+        })
+}
+
+/// Compare two version strings.
+///
+/// Equivalent to [`compare_with_flags`] with empty flags.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use libversion_sys::pure;
+///
+/// assert_eq!(pure::compare("1.0", "1.0.0"), Ordering::Equal);
+/// assert_eq!(pure::compare("1.0alpha1", "1.0"), Ordering::Less);
+/// ```
+pub fn compare(v1: &str, v2: &str) -> Ordering {
+    compare_with_flags(v1, v2, VersionFlags::empty(), VersionFlags::empty())
+}
+
+/// Compare two version strings with per-version flags.
+///
+/// Unlike [`crate::compare_with_flags`], this never panics: like the C
+/// library, it ignores everything from the first null byte on.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use libversion_sys::{VersionFlags, pure};
+///
+/// assert_eq!(
+///     pure::compare_with_flags("1.0p1", "1.0", VersionFlags::P_IS_PATCH, VersionFlags::empty()),
+///     Ordering::Greater,
+/// );
+/// ```
+pub fn compare_with_flags(
+    v1: &str,
+    v2: &str,
+    v1_flags: VersionFlags,
+    v2_flags: VersionFlags,
+) -> Ordering {
+    let padding1 = Component::padding(v1_flags);
+    let padding2 = Component::padding(v2_flags);
+    let mut components1 = components(v1, v1_flags);
+    let mut components2 = components(v2, v2_flags);
+
+    loop {
+        let (c1, c2) = match (components1.next(), components2.next()) {
+            // Once both are exhausted, one more comparison of the padding
+            // decides between a bound and a plain version.
+            (None, None) => return compare_components(&padding1, &padding2),
+            (c1, c2) => (c1.unwrap_or(padding1), c2.unwrap_or(padding2)),
+        };
+        match compare_components(&c1, &c2) {
+            Ordering::Equal => {}
+            unequal => return unequal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padding_and_bounds() {
+        let none = VersionFlags::empty();
+        assert_eq!(compare("1.0.0", "1"), Ordering::Equal);
+        assert_eq!(compare("", "0"), Ordering::Equal);
+        assert_eq!(compare("1.0a", "1.0"), Ordering::Greater);
+        assert_eq!(compare("1.0\0", "1.0.1"), Ordering::Less);
+        assert_eq!(
+            compare_with_flags("1.0", "1.0", VersionFlags::LOWER_BOUND, none),
+            Ordering::Less,
+        );
+        assert_eq!(
+            compare_with_flags("1.0", "1.0.0", VersionFlags::LOWER_BOUND, none),
+            Ordering::Less,
+        );
+        assert_eq!(
+            compare_with_flags("1.0", "1.0patch1", VersionFlags::UPPER_BOUND, none),
+            Ordering::Greater,
+        );
+        assert_eq!(
+            compare_with_flags("1.0", "1.1alpha1", VersionFlags::UPPER_BOUND, none),
+            Ordering::Less,
+        );
+    }
+
+    #[test]
+    fn letters() {
+        assert_eq!(compare("1.0alpha1", "1.0a1"), Ordering::Equal);
+        assert_eq!(compare("1.0ALPHA1", "1.0alpha1"), Ordering::Equal);
+        assert_eq!(compare("1.0alpha1", "1.0beta1"), Ordering::Less);
+        assert_eq!(compare("1.0rc1", "1.0pre1"), Ordering::Greater);
+        assert_eq!(compare("1.0a", "1.0b"), Ordering::Less);
+        assert_eq!(compare("1.0b", "1.0.1"), Ordering::Greater);
+    }
+}
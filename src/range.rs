@@ -0,0 +1,255 @@
+use std::cmp::Ordering;
+use std::ops::Bound;
+
+use crate::{Version, VersionFlags};
+
+/// A range of versions, ordered by libversion.
+///
+/// Each end is a [`Bound`] on a [`Version`], which carries its own flags. This
+/// is how [`prefix`](VersionRange::prefix) expresses a release series: its
+/// ends are the prefix interpreted with
+/// [`LOWER_BOUND`](VersionFlags::LOWER_BOUND) and
+/// [`UPPER_BOUND`](VersionFlags::UPPER_BOUND), which sort before and after
+/// every version starting with it.
+///
+/// # Panics
+///
+/// Methods comparing versions panic if a version contains an interior null
+/// byte, like [`compare_with_flags`](crate::compare_with_flags).
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::VersionRange;
+///
+/// let series = VersionRange::prefix("1.2");
+/// assert!(series.contains("1.2"));
+/// assert!(series.contains("1.2alpha1"));
+/// assert!(series.contains("1.2.5"));
+/// assert!(!series.contains("1.3"));
+///
+/// let range = VersionRange::at_least("1.0").intersect(&VersionRange::less_than("2.0"));
+/// assert!(range.contains("1.9.9"));
+/// assert!(!range.contains("2.0"));
+/// assert!(range.intersect(&VersionRange::prefix("3")).is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VersionRange {
+    start: Bound<Version>,
+    end: Bound<Version>,
+}
+
+impl VersionRange {
+    /// Create a range from its two ends.
+    pub fn new(start: Bound<Version>, end: Bound<Version>) -> Self {
+        VersionRange { start, end }
+    }
+
+    /// The range containing every version.
+    pub fn full() -> Self {
+        VersionRange::new(Bound::Unbounded, Bound::Unbounded)
+    }
+
+    /// The range containing exactly the versions equal to `version`.
+    pub fn exact(version: impl Into<Version>) -> Self {
+        let version = version.into();
+        VersionRange::new(Bound::Included(version.clone()), Bound::Included(version))
+    }
+
+    /// The versions starting with `prefix`, such as the `1.2` series for
+    /// `"1.2"`: `1.2`, `1.2alpha1` and `1.2.5`, but neither `1.1` nor `1.3`.
+    pub fn prefix(prefix: &str) -> Self {
+        VersionRange::new(
+            Bound::Included(Version::with_flags(prefix, VersionFlags::LOWER_BOUND)),
+            Bound::Included(Version::with_flags(prefix, VersionFlags::UPPER_BOUND)),
+        )
+    }
+
+    /// The versions greater than or equal to `version`.
+    pub fn at_least(version: impl Into<Version>) -> Self {
+        VersionRange::new(Bound::Included(version.into()), Bound::Unbounded)
+    }
+
+    /// The versions strictly greater than `version`.
+    pub fn greater_than(version: impl Into<Version>) -> Self {
+        VersionRange::new(Bound::Excluded(version.into()), Bound::Unbounded)
+    }
+
+    /// The versions less than or equal to `version`.
+    pub fn at_most(version: impl Into<Version>) -> Self {
+        VersionRange::new(Bound::Unbounded, Bound::Included(version.into()))
+    }
+
+    /// The versions strictly less than `version`.
+    pub fn less_than(version: impl Into<Version>) -> Self {
+        VersionRange::new(Bound::Unbounded, Bound::Excluded(version.into()))
+    }
+
+    /// The lower end of the range.
+    pub fn start(&self) -> Bound<&Version> {
+        self.start.as_ref()
+    }
+
+    /// The upper end of the range.
+    pub fn end(&self) -> Bound<&Version> {
+        self.end.as_ref()
+    }
+
+    /// Whether `version`, interpreted without flags, lies within the range.
+    pub fn contains(&self, version: &str) -> bool {
+        self.contains_with_flags(version, VersionFlags::empty())
+    }
+
+    /// Whether `version`, interpreted with `flags`, lies within the range.
+    pub fn contains_with_flags(&self, version: &str, flags: VersionFlags) -> bool {
+        let cmp = |bound: &Version| {
+            crate::compare_with_flags(bound.as_str(), version, bound.flags(), flags)
+        };
+        let above_start = match &self.start {
+            Bound::Included(start) => cmp(start) != Ordering::Greater,
+            Bound::Excluded(start) => cmp(start) == Ordering::Less,
+            Bound::Unbounded => true,
+        };
+        let below_end = match &self.end {
+            Bound::Included(end) => cmp(end) != Ordering::Less,
+            Bound::Excluded(end) => cmp(end) == Ordering::Greater,
+            Bound::Unbounded => true,
+        };
+        above_start && below_end
+    }
+
+    /// The range of versions contained in both `self` and `other`.
+    pub fn intersect(&self, other: &VersionRange) -> VersionRange {
+        // At equal versions, an excluded end is the tighter one on both sides.
+        let start = match (&self.start, &other.start) {
+            (Bound::Unbounded, bound) | (bound, Bound::Unbounded) => bound,
+            (a, b) => match bound_version(a).cmp(bound_version(b)) {
+                Ordering::Less => b,
+                Ordering::Greater => a,
+                Ordering::Equal if matches!(a, Bound::Excluded(_)) => a,
+                Ordering::Equal => b,
+            },
+        };
+        let end = match (&self.end, &other.end) {
+            (Bound::Unbounded, bound) | (bound, Bound::Unbounded) => bound,
+            (a, b) => match bound_version(a).cmp(bound_version(b)) {
+                Ordering::Less => a,
+                Ordering::Greater => b,
+                Ordering::Equal if matches!(a, Bound::Excluded(_)) => a,
+                Ordering::Equal => b,
+            },
+        };
+        VersionRange::new(start.clone(), end.clone())
+    }
+
+    /// Whether the ends of the range cross, so that no version can lie
+    /// within it.
+    ///
+    /// Only the ends themselves are compared: a range such as `(1.0, 1.0.0]`
+    /// is empty, but a range between two adjacent versions with nothing in
+    /// between is not detected.
+    pub fn is_empty(&self) -> bool {
+        match (&self.start, &self.end) {
+            (Bound::Unbounded, _) | (_, Bound::Unbounded) => false,
+            (Bound::Included(start), Bound::Included(end)) => start > end,
+            (
+                Bound::Included(start) | Bound::Excluded(start),
+                Bound::Included(end) | Bound::Excluded(end),
+            ) => start >= end,
+        }
+    }
+}
+
+impl Default for VersionRange {
+    fn default() -> Self {
+        VersionRange::full()
+    }
+}
+
+fn bound_version(bound: &Bound<Version>) -> &Version {
+    match bound {
+        Bound::Included(version) | Bound::Excluded(version) => version,
+        Bound::Unbounded => unreachable!("unbounded ends are handled by the caller"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_covers_the_series() {
+        let series = VersionRange::prefix("1.2");
+        for version in [
+            "1.2",
+            "1.2.0",
+            "1.2alpha",
+            "1.2.0.0.1",
+            "1.2.99",
+            "1.2patch1",
+        ] {
+            assert!(series.contains(version), "{version}");
+        }
+        for version in ["1.1", "1.1.99", "1.3", "1.3alpha", "2"] {
+            assert!(!series.contains(version), "{version}");
+        }
+        assert!(series.contains_with_flags("1.2p1", VersionFlags::P_IS_PATCH));
+    }
+
+    #[test]
+    fn endpoints() {
+        let closed =
+            VersionRange::new(Bound::Included("1.0".into()), Bound::Included("2.0".into()));
+        let open = VersionRange::new(Bound::Excluded("1.0".into()), Bound::Excluded("2.0".into()));
+        for range in [&closed, &open] {
+            assert!(range.contains("1.5"));
+            assert!(!range.contains("0.9"));
+            assert!(!range.contains("2.1"));
+        }
+        assert!(closed.contains("1.0.0") && closed.contains("2"));
+        assert!(!open.contains("1.0.0") && !open.contains("2"));
+        assert!(VersionRange::full().contains(""));
+        assert!(VersionRange::exact("1.0").contains("1.00"));
+    }
+
+    #[test]
+    fn intersect() {
+        let range = VersionRange::at_least("1.0").intersect(&VersionRange::at_most("2.0"));
+        assert_eq!(range.start(), Bound::Included(&"1.0".into()));
+        assert_eq!(range.end(), Bound::Included(&"2.0".into()));
+
+        // Excluded wins over included at equal versions, from either side.
+        let open = VersionRange::greater_than("1.0").intersect(&VersionRange::at_least("1.0.0"));
+        assert!(matches!(open.start(), Bound::Excluded(v) if v.as_str() == "1.0"));
+        let open = VersionRange::at_least("1.0.0").intersect(&VersionRange::greater_than("1.0"));
+        assert!(matches!(open.start(), Bound::Excluded(v) if v.as_str() == "1.0"));
+        let open = VersionRange::at_most("2").intersect(&VersionRange::less_than("2.0"));
+        assert!(matches!(open.end(), Bound::Excluded(v) if v.as_str() == "2.0"));
+
+        let series = VersionRange::prefix("1").intersect(&VersionRange::at_least("1.5"));
+        assert!(series.contains("1.9") && !series.contains("1.4") && !series.contains("2.0"));
+        assert_eq!(VersionRange::full().intersect(&series), series);
+    }
+
+    #[test]
+    fn is_empty() {
+        assert!(!VersionRange::full().is_empty());
+        assert!(!VersionRange::exact("1.0").is_empty());
+        assert!(!VersionRange::prefix("1.0").is_empty());
+        assert!(
+            VersionRange::prefix("1")
+                .intersect(&VersionRange::prefix("2"))
+                .is_empty()
+        );
+        assert!(
+            VersionRange::greater_than("1.0")
+                .intersect(&VersionRange::at_most("1.0.0"))
+                .is_empty()
+        );
+        assert!(
+            VersionRange::at_least("2")
+                .intersect(&VersionRange::at_most("1"))
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,441 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::VersionFlags;
+use crate::error::{ParseReqError, ReqErrorKind};
+
+/// A comparison operator in a [`VersionReq`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// `=`, also implied by a predicate without an operator.
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+impl Op {
+    fn as_str(self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single comparison such as `>=1.0` or `=1.2.*`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Predicate {
+    op: Op,
+    version: String,
+    wildcard: bool,
+}
+
+impl Predicate {
+    /// The comparison operator.
+    pub fn op(&self) -> Op {
+        self.op
+    }
+
+    /// The version compared against. For a wildcard such as `1.2.*` this is
+    /// the prefix before the wildcard, `1.2`, and empty for a lone `*`.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Whether the version ends in a `*` wildcard.
+    pub fn is_wildcard(&self) -> bool {
+        self.wildcard
+    }
+
+    /// Whether `version`, interpreted with `flags`, satisfies the predicate.
+    ///
+    /// A wildcard stands for every version with its prefix: it is compared
+    /// as the prefix with [`LOWER_BOUND`](VersionFlags::LOWER_BOUND) where the
+    /// lowest such version matters and with
+    /// [`UPPER_BOUND`](VersionFlags::UPPER_BOUND) where the highest does.
+    pub fn matches_with_flags(&self, version: &str, flags: VersionFlags) -> bool {
+        let (lower, upper) = if self.wildcard {
+            (VersionFlags::LOWER_BOUND, VersionFlags::UPPER_BOUND)
+        } else {
+            (VersionFlags::empty(), VersionFlags::empty())
+        };
+        let cmp = |bound| crate::compare_with_flags(version, &self.version, flags, bound);
+        let within = || cmp(lower) != Ordering::Less && cmp(upper) != Ordering::Greater;
+        match self.op {
+            Op::Eq => within(),
+            Op::Ne => !within(),
+            Op::Lt => cmp(lower) == Ordering::Less,
+            Op::Le => cmp(upper) != Ordering::Greater,
+            Op::Gt => cmp(upper) == Ordering::Greater,
+            Op::Ge => cmp(lower) != Ordering::Less,
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.wildcard, self.version.is_empty()) {
+            (true, true) => write!(f, "{}*", self.op),
+            (true, false) => write!(f, "{}{}.*", self.op, self.version),
+            (false, _) => write!(f, "{}{}", self.op, self.version),
+        }
+    }
+}
+
+/// A version requirement such as `>=1.0, <2.0 || =3.1`, evaluated with
+/// libversion's ordering.
+///
+/// A requirement is a list of alternatives separated by `||`, each of which
+/// is a list of [predicates](Predicate) separated by `,` that must all hold.
+/// A predicate is one of the operators `=`, `!=`, `<`, `<=`, `>` or `>=`
+/// followed by a version; without an operator, `=` is implied. The last
+/// component of a version may be the wildcard `*`, so that `1.2.*` matches
+/// the whole `1.2` series through the bound flags, and a lone `*` matches
+/// everything. Whitespace is allowed around operators and separators.
+///
+/// # Panics
+///
+/// [`matches`](VersionReq::matches) panics if the version contains an
+/// interior null byte, like [`compare_with_flags`](crate::compare_with_flags).
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::VersionReq;
+///
+/// let req = VersionReq::parse(">=1.0, <2.0 || =3.1").unwrap();
+/// assert!(req.matches("1.5"));
+/// assert!(req.matches("3.1.0"));
+/// assert!(!req.matches("2.0"));
+/// assert!(!req.matches("1.0alpha1"));
+///
+/// let series = VersionReq::parse("1.2.*, != 1.2.3").unwrap();
+/// assert!(series.matches("1.2alpha1"));
+/// assert!(series.matches("1.2.10"));
+/// assert!(!series.matches("1.2.3"));
+/// assert!(!series.matches("1.3"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VersionReq {
+    alternatives: Vec<Vec<Predicate>>,
+}
+
+impl VersionReq {
+    /// Parse a requirement string.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseReqError`] locating the first syntax error.
+    pub fn parse(req: &str) -> Result<Self, ParseReqError> {
+        Parser { input: req, pos: 0 }.parse()
+    }
+
+    /// Whether `version`, interpreted without flags, satisfies the
+    /// requirement.
+    pub fn matches(&self, version: &str) -> bool {
+        self.matches_with_flags(version, VersionFlags::empty())
+    }
+
+    /// Whether `version`, interpreted with `flags`, satisfies the
+    /// requirement.
+    pub fn matches_with_flags(&self, version: &str, flags: VersionFlags) -> bool {
+        self.alternatives.iter().any(|predicates| {
+            predicates
+                .iter()
+                .all(|predicate| predicate.matches_with_flags(version, flags))
+        })
+    }
+
+    /// The `||`-separated alternatives, each a list of predicates that must
+    /// all hold.
+    pub fn alternatives(&self) -> impl Iterator<Item = &[Predicate]> {
+        self.alternatives.iter().map(Vec::as_slice)
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = ParseReqError;
+
+    fn from_str(s: &str) -> Result<Self, ParseReqError> {
+        VersionReq::parse(s)
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, predicates) in self.alternatives.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" || ")?;
+            }
+            for (j, predicate) in predicates.iter().enumerate() {
+                if j > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{predicate}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_operator(c: char) -> bool {
+    matches!(c, '=' | '!' | '<' | '>')
+}
+
+fn is_version(c: char) -> bool {
+    !c.is_whitespace() && !is_operator(c) && !matches!(c, ',' | '|' | '\0')
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    /// An error about the character at the current position, or about the
+    /// missing input at the end.
+    fn error_here(&self, kind: impl FnOnce(char) -> ReqErrorKind) -> ParseReqError {
+        match self.peek() {
+            Some(c) => ParseReqError::new(kind(c), self.pos..self.pos + c.len_utf8()),
+            None => ParseReqError::new(ReqErrorKind::ExpectedPredicate, self.pos..self.pos),
+        }
+    }
+
+    fn parse(mut self) -> Result<VersionReq, ParseReqError> {
+        let mut alternatives = Vec::new();
+        loop {
+            alternatives.push(self.parse_predicates()?);
+            if self.peek().is_none() {
+                return Ok(VersionReq { alternatives });
+            }
+            if !self.rest().starts_with("||") {
+                return Err(self.error_here(ReqErrorKind::UnexpectedChar));
+            }
+            self.pos += 2;
+        }
+    }
+
+    fn parse_predicates(&mut self) -> Result<Vec<Predicate>, ParseReqError> {
+        let mut predicates = vec![self.parse_predicate()?];
+        self.skip_whitespace();
+        while self.peek() == Some(',') {
+            self.pos += 1;
+            predicates.push(self.parse_predicate()?);
+            self.skip_whitespace();
+        }
+        Ok(predicates)
+    }
+
+    fn parse_predicate(&mut self) -> Result<Predicate, ParseReqError> {
+        self.skip_whitespace();
+        let op_start = self.pos;
+        let op = match self.take_while(is_operator) {
+            "" | "=" => Op::Eq,
+            "!=" => Op::Ne,
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            other => {
+                return Err(ParseReqError::new(
+                    ReqErrorKind::InvalidOperator(other.to_owned()),
+                    op_start..self.pos,
+                ));
+            }
+        };
+        let op_end = self.pos;
+
+        self.skip_whitespace();
+        let version_start = self.pos;
+        let version = self.take_while(is_version);
+        if version.is_empty() {
+            return Err(match self.peek() {
+                Some(',' | '|') | None if op_end > op_start => {
+                    ParseReqError::new(ReqErrorKind::MissingVersion, op_start..op_end)
+                }
+                Some(',' | '|') => self.error_here(|_| ReqErrorKind::ExpectedPredicate),
+                _ => self.error_here(ReqErrorKind::UnexpectedChar),
+            });
+        }
+
+        let (version, wildcard) = match version.find('*') {
+            None => (version, false),
+            Some(star) => {
+                // `*` must be the whole version or the last component,
+                // directly after a separator. Like libversion, anything but
+                // an ASCII letter or digit separates components.
+                let prefix = &version[..star];
+                let valid = star == version.len() - 1
+                    && (prefix.is_empty()
+                        || !prefix.ends_with(|c: char| c.is_ascii_alphanumeric()));
+                if !valid {
+                    let star = version_start + star;
+                    return Err(ParseReqError::new(
+                        ReqErrorKind::MisplacedWildcard,
+                        star..star + 1,
+                    ));
+                }
+                (
+                    prefix.trim_end_matches(|c: char| !c.is_ascii_alphanumeric()),
+                    true,
+                )
+            }
+        };
+
+        Ok(Predicate {
+            op,
+            version: version.to_owned(),
+            wildcard,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn err(req: &str) -> (ReqErrorKind, std::ops::Range<usize>) {
+        let err = VersionReq::parse(req).unwrap_err();
+        (err.kind().clone(), err.span())
+    }
+
+    #[test]
+    fn operators() {
+        let cases = [
+            (
+                "=1.0",
+                ["1.0", "1.0.0"].as_slice(),
+                ["1.0.1", "0.9"].as_slice(),
+            ),
+            ("1.0", &["1.00"], &["1.0a"]),
+            ("!=1.0", &["1.0.1", "0.9"], &["1.0.0"]),
+            ("<1.0", &["0.9", "1.0rc1"], &["1.0", "1.0patch1"]),
+            ("<=1.0", &["0.9", "1.0"], &["1.0.1"]),
+            (">1.0", &["1.0.1", "1.0patch1"], &["1.0", "1.0rc1"]),
+            (">=1.0", &["1.0", "2"], &["0.9"]),
+        ];
+        let predicate = &VersionReq::parse("1.2é-*").unwrap().alternatives[0][0];
+        assert_eq!(predicate.version(), "1.2");
+        for (req, matching, other) in cases {
+            let req = VersionReq::parse(req).unwrap();
+            for version in matching {
+                assert!(req.matches(version), "{req} should match {version}");
+            }
+            for version in other {
+                assert!(!req.matches(version), "{req} should not match {version}");
+            }
+        }
+    }
+
+    #[test]
+    fn wildcards() {
+        let cases = [
+            (
+                "1.2.*",
+                ["1.2", "1.2alpha", "1.2.99"].as_slice(),
+                ["1.1", "1.3"].as_slice(),
+            ),
+            ("!=1.2.*", &["1.1", "1.3"], &["1.2", "1.2.5"]),
+            ("<1.2.*", &["1.1.99"], &["1.2alpha", "1.2"]),
+            ("<=1.2.*", &["1.2.99", "1.2patch1"], &["1.3alpha"]),
+            (">1.2.*", &["1.3alpha", "1.3"], &["1.2.99"]),
+            (">=1.2.*", &["1.2alpha", "1.2"], &["1.1.99"]),
+            ("1-*", &["1.0", "1.99"], &["2.0"]),
+            ("*", &["", "0", "1.0", "99"], &[]),
+            // Non-ASCII characters are separators, as in libversion.
+            ("1é*", &["1.0", "1.99"], &["2.0"]),
+        ];
+        for (req, matching, other) in cases {
+            let req = VersionReq::parse(req).unwrap();
+            for version in matching {
+                assert!(req.matches(version), "{req} should match {version}");
+            }
+            for version in other {
+                assert!(!req.matches(version), "{req} should not match {version}");
+            }
+        }
+    }
+
+    #[test]
+    fn and_or() {
+        let req: VersionReq = " >= 1.0 ,<2.0||=3.1 ".parse().unwrap();
+        assert_eq!(
+            req.alternatives().map(<[_]>::len).collect::<Vec<_>>(),
+            [2, 1]
+        );
+        assert!(req.matches("1.5") && req.matches("3.1"));
+        assert!(!req.matches("0.9") && !req.matches("2.0") && !req.matches("3.2"));
+        assert!(req.matches_with_flags("1.0p1", VersionFlags::P_IS_PATCH));
+        assert!(!req.matches("1.0p1"));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for (req, display) in [
+            (">=1.0,<2.0||3.1", ">=1.0, <2.0 || =3.1"),
+            ("!= 1.2-*", "!=1.2.*"),
+            ("*", "=*"),
+        ] {
+            let parsed = VersionReq::parse(req).unwrap();
+            assert_eq!(parsed.to_string(), display);
+            assert_eq!(VersionReq::parse(display).unwrap(), parsed);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        use ReqErrorKind::*;
+
+        assert_eq!(err(""), (ExpectedPredicate, 0..0));
+        assert_eq!(err("  "), (ExpectedPredicate, 2..2));
+        assert_eq!(err(">=1.0,"), (ExpectedPredicate, 6..6));
+        assert_eq!(err("1.0 ||, 2.0"), (ExpectedPredicate, 6..7));
+        assert_eq!(err(">= , 1"), (MissingVersion, 0..2));
+        assert_eq!(err("1 || <"), (MissingVersion, 5..6));
+        assert_eq!(err("1.0 2.0"), (UnexpectedChar('2'), 4..5));
+        assert_eq!(err("1.0 | 2.0"), (UnexpectedChar('|'), 4..5));
+        assert_eq!(err("1.0\x001"), (UnexpectedChar('\0'), 3..4));
+        assert_eq!(err("=>1"), (InvalidOperator("=>".to_owned()), 0..2));
+        assert_eq!(err("1, <>2"), (InvalidOperator("<>".to_owned()), 3..5));
+        assert_eq!(err("1.*.2"), (MisplacedWildcard, 2..3));
+        assert_eq!(err(">1.2*"), (MisplacedWildcard, 4..5));
+    }
+}
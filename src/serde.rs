@@ -0,0 +1,269 @@
+//! [`Serialize`] and [`Deserialize`] implementations, enabled by the `serde`
+//! feature.
+//!
+//! [`VersionStr`] and [`VersionBuf`] serialize as their string, spelled
+//! exactly as given. [`VersionFlags`] serialize as a list of flag names, such
+//! as `["P_IS_PATCH", "LOWER_BOUND"]`. A [`Version`] without flags serializes
+//! as its string too; one with flags serializes as a struct with `version`
+//! and `flags` fields, and both forms deserialize. Formats that are not
+//! human-readable always get the struct form.
+//!
+//! Since a version with an interior null byte cannot be compared,
+//! deserializing one is an error rather than a panic later on.
+//!
+//! [`sorted`] keeps a plain list of version strings sorted in libversion's
+//! order, for use with `#[serde(with = "libversion_sys::serde::sorted")]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use libversion_sys::{Version, VersionFlags};
+//!
+//! let version = Version::with_flags("9.6p1", VersionFlags::P_IS_PATCH);
+//! let json = serde_json::to_string(&version).unwrap();
+//! assert_eq!(json, r#"{"version":"9.6p1","flags":["P_IS_PATCH"]}"#);
+//!
+//! let version: Version = serde_json::from_str(r#""1.0.0""#).unwrap();
+//! assert_eq!(version.as_str(), "1.0.0");
+//! assert_eq!(version.flags(), VersionFlags::empty());
+//! ```
+
+use std::fmt;
+
+use ::serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use ::serde::ser::{Error as _, Serialize, SerializeStruct, Serializer};
+
+use crate::{Version, VersionBuf, VersionFlags, VersionStr};
+
+const FLAG_NAMES: &[&str] = &["P_IS_PATCH", "ANY_IS_PATCH", "LOWER_BOUND", "UPPER_BOUND"];
+const VERSION_FIELDS: &[&str] = &["version", "flags"];
+
+/// The error for a version with an interior null byte, at the given
+/// position.
+struct InteriorNul(usize);
+
+impl fmt::Display for InteriorNul {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "version contains an interior null byte at position {}",
+            self.0
+        )
+    }
+}
+
+fn find_nul(version: &str) -> Result<(), InteriorNul> {
+    match version.find('\0') {
+        Some(position) => Err(InteriorNul(position)),
+        None => Ok(()),
+    }
+}
+
+fn check_nul<E: de::Error>(version: &str) -> Result<(), E> {
+    find_nul(version).map_err(E::custom)
+}
+
+impl Serialize for VersionFlags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let unknown = self.bits() & !VersionFlags::all().bits();
+        if unknown != 0 {
+            return Err(S::Error::custom(format_args!(
+                "unknown version flag bits {unknown:#x}"
+            )));
+        }
+        serializer.collect_seq(self.iter_names().map(|(name, _)| name))
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionFlags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FlagsVisitor;
+
+        impl<'de> Visitor<'de> for FlagsVisitor {
+            type Value = VersionFlags;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a list of version flag names")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<VersionFlags, A::Error> {
+                let mut flags = VersionFlags::empty();
+                while let Some(name) = seq.next_element::<String>()? {
+                    flags |= VersionFlags::from_name(&name)
+                        .ok_or_else(|| de::Error::unknown_variant(&name, FLAG_NAMES))?;
+                }
+                Ok(flags)
+            }
+        }
+
+        deserializer.deserialize_seq(FlagsVisitor)
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.flags().is_empty() && serializer.is_human_readable() {
+            return serializer.serialize_str(self.as_str());
+        }
+        let mut state = serializer.serialize_struct("Version", 2)?;
+        state.serialize_field("version", self.as_str())?;
+        state.serialize_field("flags", &self.flags())?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct VersionVisitor;
+
+        impl VersionVisitor {
+            fn version<E: de::Error>(version: String, flags: VersionFlags) -> Result<Version, E> {
+                check_nul(&version)?;
+                Ok(Version::with_flags(version, flags))
+            }
+        }
+
+        impl<'de> Visitor<'de> for VersionVisitor {
+            type Value = Version;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a version string or a struct with version and flags")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Version, E> {
+                VersionVisitor::version(v.to_owned(), VersionFlags::empty())
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Version, E> {
+                VersionVisitor::version(v, VersionFlags::empty())
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Version, A::Error> {
+                let version = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let flags = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                VersionVisitor::version(version, flags)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Version, A::Error> {
+                let mut version = None;
+                let mut flags = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "version" if version.is_some() => {
+                            return Err(de::Error::duplicate_field("version"));
+                        }
+                        "version" => version = Some(map.next_value()?),
+                        "flags" if flags.is_some() => {
+                            return Err(de::Error::duplicate_field("flags"));
+                        }
+                        "flags" => flags = Some(map.next_value()?),
+                        _ => return Err(de::Error::unknown_field(&key, VERSION_FIELDS)),
+                    }
+                }
+                let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+                VersionVisitor::version(version, flags.unwrap_or_default())
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(VersionVisitor)
+        } else {
+            deserializer.deserialize_struct("Version", VERSION_FIELDS, VersionVisitor)
+        }
+    }
+}
+
+impl Serialize for VersionStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for &'a VersionStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = <&str>::deserialize(deserializer)?;
+        check_nul(version)?;
+        Ok(VersionStr::new(version))
+    }
+}
+
+impl Serialize for VersionBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = String::deserialize(deserializer)?;
+        check_nul(&version)?;
+        Ok(VersionBuf::from(version))
+    }
+}
+
+/// Serialize and deserialize a list of plain version strings, sorted in
+/// libversion's order with no flags.
+///
+/// The list is sorted when serialized and again when deserialized, with
+/// [`sort_versions`](crate::sort_versions), so the field can stay a
+/// `Vec<String>` while always being in version order. Each version keeps its
+/// spelling, and versions that compare equal keep their relative order.
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Releases {
+///     #[serde(with = "libversion_sys::serde::sorted")]
+///     versions: Vec<String>,
+/// }
+///
+/// let releases: Releases = serde_json::from_str(r#"{"versions":["1.10","1.2","1.2rc1"]}"#).unwrap();
+/// assert_eq!(releases.versions, ["1.2rc1", "1.2", "1.10"]);
+///
+/// let releases = Releases { versions: vec!["2.0".into(), "1.0".into()] };
+/// assert_eq!(serde_json::to_string(&releases).unwrap(), r#"{"versions":["1.0","2.0"]}"#);
+/// ```
+pub mod sorted {
+    use ::serde::de::{Deserialize, Deserializer};
+    use ::serde::ser::{Error as _, Serializer};
+
+    use super::{check_nul, find_nul};
+    use crate::{VersionFlags, sort_versions};
+
+    /// Serialize `versions` as a sequence, in ascending version order. A
+    /// version with an interior null byte is an error.
+    pub fn serialize<T, S>(versions: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<str>,
+        S: Serializer,
+    {
+        let mut sorted: Vec<&str> = versions.iter().map(T::as_ref).collect();
+        for version in &sorted {
+            find_nul(version).map_err(S::Error::custom)?;
+        }
+        sort_versions(&mut sorted, VersionFlags::empty());
+        serializer.collect_seq(sorted)
+    }
+
+    /// Deserialize a sequence of versions and sort it in ascending version
+    /// order. A version with an interior null byte is an error.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: AsRef<str> + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let mut versions = Vec::<T>::deserialize(deserializer)?;
+        for version in &versions {
+            check_nul(version.as_ref())?;
+        }
+        sort_versions(&mut versions, VersionFlags::empty());
+        Ok(versions)
+    }
+}
@@ -0,0 +1,234 @@
+//! Sorting and selection over collections of version strings.
+//!
+//! Every function takes the [`VersionFlags`] to interpret all versions with,
+//! and panics if a version contains an interior null byte, like
+//! [`compare_with_flags`](crate::compare_with_flags).
+
+use std::cmp::Ordering;
+
+use crate::{SortKey, VersionFlags};
+
+fn cmp(v1: &str, v2: &str, flags: VersionFlags) -> Ordering {
+    crate::compare_with_flags(v1, v2, flags, flags)
+}
+
+fn sort_key(version: &str, flags: VersionFlags) -> SortKey {
+    if let Some(position) = version.find('\0') {
+        panic!("version contains an interior null byte at position {position}");
+    }
+    SortKey::new(version, flags)
+}
+
+/// Stably sort `items` by the version `key` borrows from each, parsing every
+/// version only once.
+fn sort_by_sort_key<T>(items: &mut [T], flags: VersionFlags, mut key: impl FnMut(&T) -> &str) {
+    items.sort_by_cached_key(|item| sort_key(key(item), flags));
+}
+
+/// Sort version strings in ascending order.
+///
+/// The sort is stable: versions that compare equal, such as `1.0` and
+/// `1.0.0`, keep their relative order. Each version is parsed only once,
+/// into a [`SortKey`], rather than on every comparison.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, sort_versions};
+///
+/// let mut versions = ["1.10", "1.2", "1.0alpha1", "1.0"];
+/// sort_versions(&mut versions, VersionFlags::empty());
+/// assert_eq!(versions, ["1.0alpha1", "1.0", "1.2", "1.10"]);
+/// ```
+pub fn sort_versions<S: AsRef<str>>(versions: &mut [S], flags: VersionFlags) {
+    sort_by_sort_key(versions, flags, |v| v.as_ref());
+}
+
+/// Sort items in ascending order of the version string `key` borrows from
+/// each of them.
+///
+/// The sort is stable, and like [`sort_versions`] parses each version only
+/// once.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, sort_versions_by_key};
+///
+/// struct Release {
+///     version: String,
+/// }
+///
+/// let mut releases: Vec<Release> = ["2.0", "1.9", "2.0rc1"]
+///     .map(|v| Release { version: v.to_owned() })
+///     .into();
+/// sort_versions_by_key(&mut releases, VersionFlags::empty(), |r| &r.version);
+/// let sorted: Vec<&str> = releases.iter().map(|r| r.version.as_str()).collect();
+/// assert_eq!(sorted, ["1.9", "2.0rc1", "2.0"]);
+/// ```
+pub fn sort_versions_by_key<T, F>(items: &mut [T], flags: VersionFlags, key: F)
+where
+    F: FnMut(&T) -> &str,
+{
+    sort_by_sort_key(items, flags, key);
+}
+
+/// The greatest version, or `None` if there are none.
+///
+/// If several versions are equally great, the last one is returned, like
+/// [`Iterator::max_by`].
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, max_version};
+///
+/// let versions = ["1.0", "1.0patch1", "1.0p1"];
+/// assert_eq!(max_version(versions, VersionFlags::empty()), Some("1.0patch1"));
+/// assert_eq!(max_version(versions, VersionFlags::P_IS_PATCH), Some("1.0p1"));
+/// ```
+pub fn max_version<I>(versions: I, flags: VersionFlags) -> Option<I::Item>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    versions
+        .into_iter()
+        .max_by(|a, b| cmp(a.as_ref(), b.as_ref(), flags))
+}
+
+/// The least version, or `None` if there are none.
+///
+/// If several versions are equally least, the first one is returned, like
+/// [`Iterator::min_by`].
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, min_version};
+///
+/// let versions = vec!["1.0".to_owned(), "1.0rc1".to_owned()];
+/// assert_eq!(min_version(&versions, VersionFlags::empty()).unwrap(), "1.0rc1");
+/// ```
+pub fn min_version<I>(versions: I, flags: VersionFlags) -> Option<I::Item>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    versions
+        .into_iter()
+        .min_by(|a, b| cmp(a.as_ref(), b.as_ref(), flags))
+}
+
+/// Remove consecutive versions that compare equal, keeping the first of
+/// each run, like [`Vec::dedup`]. Sort first to remove all duplicates.
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, dedup_equal_versions, sort_versions};
+///
+/// let mut versions = vec!["1.0.0", "2", "1.0", "2.0", "1"];
+/// sort_versions(&mut versions, VersionFlags::empty());
+/// dedup_equal_versions(&mut versions, VersionFlags::empty());
+/// assert_eq!(versions, ["1.0.0", "2"]);
+/// ```
+pub fn dedup_equal_versions<S: AsRef<str>>(versions: &mut Vec<S>, flags: VersionFlags) {
+    versions.dedup_by(|a, b| cmp(a.as_ref(), b.as_ref(), flags) == Ordering::Equal);
+}
+
+/// The greatest version satisfying `predicate`, or `None` if none does.
+///
+/// Ties are broken like [`max_version`].
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::{VersionFlags, VersionReq, latest_matching};
+///
+/// let req = VersionReq::parse("1.*").unwrap();
+/// let versions = ["0.9", "1.4", "1.12", "2.0"];
+/// let latest = latest_matching(versions, VersionFlags::empty(), |v| req.matches(v));
+/// assert_eq!(latest, Some("1.12"));
+/// ```
+pub fn latest_matching<I, P>(versions: I, flags: VersionFlags, mut predicate: P) -> Option<I::Item>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+    P: FnMut(&str) -> bool,
+{
+    max_version(
+        versions.into_iter().filter(|v| predicate(v.as_ref())),
+        flags,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_is_stable() {
+        let mut versions = vec![
+            "1.0.0".to_owned(),
+            "0.9".to_owned(),
+            "1.0".to_owned(),
+            "1".to_owned(),
+        ];
+        sort_versions(&mut versions, VersionFlags::empty());
+        assert_eq!(versions, ["0.9", "1.0.0", "1.0", "1"]);
+
+        let mut pairs = [(1, "1.0"), (2, "0.1"), (3, "1"), (4, "1.0a")];
+        sort_versions_by_key(&mut pairs, VersionFlags::empty(), |p| p.1);
+        assert_eq!(pairs.map(|p| p.0), [2, 1, 3, 4]);
+    }
+
+    #[test]
+    fn flags_apply_to_every_version() {
+        let mut versions = ["1.0p1", "1.0", "1.0a1"];
+        sort_versions(&mut versions, VersionFlags::P_IS_PATCH);
+        assert_eq!(versions, ["1.0a1", "1.0", "1.0p1"]);
+        sort_versions(&mut versions, VersionFlags::empty());
+        assert_eq!(versions, ["1.0a1", "1.0p1", "1.0"]);
+    }
+
+    #[test]
+    fn selection() {
+        let none = VersionFlags::empty();
+        let empty: [&str; 0] = [];
+        assert_eq!(max_version(empty, none), None);
+        assert_eq!(min_version(empty, none), None);
+
+        // Ties: last maximum, first minimum.
+        let versions = ["1.0", "0.5", "1.0.0", "0.5.0"];
+        assert_eq!(max_version(versions, none), Some("1.0.0"));
+        assert_eq!(min_version(versions, none), Some("0.5"));
+
+        assert_eq!(
+            latest_matching(versions, none, |v| v.starts_with('0')),
+            Some("0.5.0")
+        );
+        assert_eq!(latest_matching(versions, none, |_| false), None);
+    }
+
+    #[test]
+    fn dedup() {
+        let mut versions = vec!["1", "1.0", "1.0a", "1.0", "1.00.0"];
+        dedup_equal_versions(&mut versions, VersionFlags::empty());
+        assert_eq!(versions, ["1", "1.0a", "1.0"]);
+    }
+
+    #[test]
+    fn shuffled_series() {
+        let mut versions: Vec<String> = (0..100).map(|i| format!("1.{}", (i * 37) % 100)).collect();
+        sort_versions(&mut versions, VersionFlags::empty());
+        let expected: Vec<String> = (0..100).map(|i| format!("1.{i}")).collect();
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "version contains an interior null byte at position 3")]
+    fn sort_panics_on_nul() {
+        sort_versions(&mut ["1.0", "1.0\x001"], VersionFlags::empty());
+    }
+}
@@ -0,0 +1,403 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use crate::{SortKey, VersionFlags, parse};
+
+/// An owned version string ordered by libversion, together with the
+/// [`VersionFlags`] it should be interpreted with.
+///
+/// [`Ord`] delegates to [`compare_with_flags`](crate::compare_with_flags),
+/// passing each side's own flags, and [`Eq`] and [`Hash`] agree with it:
+/// `"1.0"`, `"1.0.0"` and `"1.00"` are equal and hash the same, so `Version`
+/// can be used as a key in both `BTreeMap` and `HashMap`. Versions with
+/// different flags can still be equal, e.g. `"1.0p1"` with
+/// [`P_IS_PATCH`](VersionFlags::P_IS_PATCH) and `"1.0post1"` without. The
+/// original spelling is kept and returned by [`as_str`](Version::as_str) and
+/// [`Display`](fmt::Display).
+///
+/// # Panics
+///
+/// Comparing a `Version` that contains an interior null byte panics, like
+/// [`compare_with_flags`](crate::compare_with_flags).
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use libversion_sys::Version;
+///
+/// let mut versions: Vec<Version> = ["1.10", "1.2", "1.0alpha1"]
+///     .into_iter()
+///     .map(Version::from)
+///     .collect();
+/// versions.sort();
+/// assert_eq!(versions, ["1.0alpha1", "1.2", "1.10"].map(Version::from));
+///
+/// let set: HashSet<Version> = ["1.0", "1.0.0", "1.00"].map(Version::from).into();
+/// assert_eq!(set.len(), 1);
+/// ```
+///
+/// Flags travel with the version, so they cannot be forgotten at a
+/// comparison site:
+///
+/// ```
+/// use libversion_sys::{Version, VersionFlags};
+///
+/// let patched = Version::with_flags("1.0p1", VersionFlags::P_IS_PATCH);
+/// assert!(patched > Version::new("1.0"));
+/// assert!(Version::new("1.0p1") < Version::new("1.0"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Version {
+    inner: String,
+    flags: VersionFlags,
+}
+
+impl Version {
+    /// Create a version with no flags from anything convertible into a
+    /// [`String`].
+    pub fn new(version: impl Into<String>) -> Self {
+        Version::with_flags(version, VersionFlags::empty())
+    }
+
+    /// Create a version that is interpreted with `flags` whenever it is
+    /// compared.
+    pub fn with_flags(version: impl Into<String>, flags: VersionFlags) -> Self {
+        Version {
+            inner: version.into(),
+            flags,
+        }
+    }
+
+    /// The version string as originally given.
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    /// The flags this version is compared with.
+    pub fn flags(&self) -> VersionFlags {
+        self.flags
+    }
+
+    /// Consume the version, returning the original string.
+    pub fn into_string(self) -> String {
+        self.inner
+    }
+
+    /// Parse the version once into a [`SortKey`] with the same ordering.
+    pub fn sort_key(&self) -> SortKey {
+        SortKey::new(&self.inner, self.flags)
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        crate::compare_with_flags(&self.inner, &other.inner, self.flags, other.flags)
+    }
+}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        parse::hash_version(&self.inner, self.flags, state);
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.inner)
+    }
+}
+
+impl From<&str> for Version {
+    fn from(version: &str) -> Self {
+        Version::new(version)
+    }
+}
+
+impl From<String> for Version {
+    fn from(version: String) -> Self {
+        Version::new(version)
+    }
+}
+
+impl From<Version> for String {
+    fn from(version: Version) -> Self {
+        version.inner
+    }
+}
+
+impl AsRef<str> for Version {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+
+/// A borrowed version string ordered by libversion, the unsized counterpart
+/// of [`VersionBuf`].
+///
+/// `VersionStr` relates to [`VersionBuf`] as [`Path`](std::path::Path) relates
+/// to [`PathBuf`](std::path::PathBuf): it wraps a `str` without copying it and
+/// gives it libversion's [`Ord`], [`Eq`] and [`Hash`]. This allows comparing
+/// and looking up versions borrowed from a larger buffer without allocating
+/// a [`Version`] for each.
+///
+/// # Panics
+///
+/// Comparing a `VersionStr` that contains an interior null byte panics, like
+/// [`compare`](crate::compare).
+///
+/// # Examples
+///
+/// ```
+/// use libversion_sys::VersionStr;
+///
+/// let manifest = "foo 1.0.0\nbar 1.0rc1\n";
+/// let versions: Vec<&VersionStr> = manifest
+///     .lines()
+///     .filter_map(|line| line.split_once(' '))
+///     .map(|(_, version)| VersionStr::new(version))
+///     .collect();
+///
+/// assert_eq!(versions[0], VersionStr::new("1.0"));
+/// assert!(versions[1] < versions[0]);
+/// ```
+#[repr(transparent)]
+pub struct VersionStr {
+    inner: str,
+}
+
+impl VersionStr {
+    /// Wrap a string slice as a `VersionStr`. This is a cost-free conversion.
+    pub fn new<S: AsRef<str> + ?Sized>(version: &S) -> &VersionStr {
+        let version: &str = version.as_ref();
+        // SAFETY: VersionStr is a #[repr(transparent)] wrapper around str.
+        unsafe { &*(version as *const str as *const VersionStr) }
+    }
+
+    /// The underlying string slice.
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    /// Copy the version into an owned [`VersionBuf`].
+    pub fn to_version_buf(&self) -> VersionBuf {
+        VersionBuf::from(&self.inner)
+    }
+}
+
+impl PartialEq for VersionStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for VersionStr {}
+
+impl PartialOrd for VersionStr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VersionStr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        crate::compare(&self.inner, &other.inner)
+    }
+}
+
+impl Hash for VersionStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        parse::hash_version(&self.inner, VersionFlags::empty(), state);
+    }
+}
+
+impl fmt::Debug for VersionStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl fmt::Display for VersionStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.inner)
+    }
+}
+
+impl AsRef<str> for VersionStr {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl AsRef<VersionStr> for VersionStr {
+    fn as_ref(&self) -> &VersionStr {
+        self
+    }
+}
+
+impl AsRef<VersionStr> for str {
+    fn as_ref(&self) -> &VersionStr {
+        VersionStr::new(self)
+    }
+}
+
+impl AsRef<VersionStr> for String {
+    fn as_ref(&self) -> &VersionStr {
+        VersionStr::new(self)
+    }
+}
+
+impl<'a> From<&'a str> for &'a VersionStr {
+    fn from(version: &'a str) -> Self {
+        VersionStr::new(version)
+    }
+}
+
+impl ToOwned for VersionStr {
+    type Owned = VersionBuf;
+
+    fn to_owned(&self) -> VersionBuf {
+        self.to_version_buf()
+    }
+}
+
+/// An owned version string, the owned counterpart of [`VersionStr`].
+///
+/// `VersionBuf` dereferences to [`VersionStr`] and implements
+/// [`Borrow<VersionStr>`](Borrow), so a `HashMap<VersionBuf, _>` or
+/// `BTreeMap<VersionBuf, _>` can be queried with a borrowed `&VersionStr`.
+/// Its [`Ord`], [`Eq`] and [`Hash`] are those of [`VersionStr`].
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use libversion_sys::{VersionBuf, VersionStr};
+///
+/// let mut downloads = HashMap::new();
+/// downloads.insert(VersionBuf::from("2.0"), 10);
+/// assert_eq!(downloads[VersionStr::new("2.0.0")], 10);
+/// ```
+#[derive(Clone)]
+pub struct VersionBuf {
+    inner: String,
+}
+
+impl VersionBuf {
+    /// Coerce to a [`VersionStr`] slice.
+    pub fn as_version_str(&self) -> &VersionStr {
+        self
+    }
+
+    /// Consume the buffer, returning the original string.
+    pub fn into_string(self) -> String {
+        self.inner
+    }
+}
+
+impl PartialEq for VersionBuf {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for VersionBuf {}
+
+impl PartialOrd for VersionBuf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VersionBuf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl Hash for VersionBuf {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl Deref for VersionBuf {
+    type Target = VersionStr;
+
+    fn deref(&self) -> &VersionStr {
+        VersionStr::new(&self.inner)
+    }
+}
+
+impl Borrow<VersionStr> for VersionBuf {
+    fn borrow(&self) -> &VersionStr {
+        self
+    }
+}
+
+impl AsRef<VersionStr> for VersionBuf {
+    fn as_ref(&self) -> &VersionStr {
+        self
+    }
+}
+
+impl AsRef<str> for VersionBuf {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl fmt::Debug for VersionBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for VersionBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl From<&str> for VersionBuf {
+    fn from(version: &str) -> Self {
+        VersionBuf {
+            inner: version.to_owned(),
+        }
+    }
+}
+
+impl From<String> for VersionBuf {
+    fn from(version: String) -> Self {
+        VersionBuf { inner: version }
+    }
+}
+
+impl From<&VersionStr> for VersionBuf {
+    fn from(version: &VersionStr) -> Self {
+        version.to_version_buf()
+    }
+}
+
+impl From<VersionBuf> for String {
+    fn from(version: VersionBuf) -> Self {
+        version.inner
+    }
+}
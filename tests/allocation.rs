@@ -0,0 +1,66 @@
+//! Checks that comparing short versions does not touch the heap.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::cmp::Ordering;
+
+use libversion_sys::{VersionFlags, VersionStr, compare, compare_with_flags, try_compare};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn compare_does_not_allocate() {
+    let (result, count) = allocations(|| compare("1.0alpha1", "1.0"));
+    assert_eq!(result, Ordering::Less);
+    assert_eq!(count, 0);
+
+    let (result, count) = allocations(|| {
+        compare_with_flags(
+            "1.0p1",
+            "1.0",
+            VersionFlags::P_IS_PATCH,
+            VersionFlags::empty(),
+        )
+    });
+    assert_eq!(result, Ordering::Greater);
+    assert_eq!(count, 0);
+
+    let (result, count) = allocations(|| try_compare("1.0", "1.0.0"));
+    assert_eq!(result, Ok(Ordering::Equal));
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn version_str_does_not_allocate() {
+    let buffer = "2.0 2.0rc1";
+    let (result, count) = allocations(|| {
+        let (a, b) = buffer.split_once(' ').unwrap();
+        VersionStr::new(a) > VersionStr::new(b)
+    });
+    assert!(result);
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,396 @@
+//! Comparison test corpus following libversion's upstream
+//! `tests/compare_test.c`: flags, bounds, letter suffixes, keywords and odd
+//! separators.
+//!
+//! Every case is checked in both directions, like upstream's
+//! `version_test_symmetrical`, through the crate-level API, so it exercises
+//! whichever backend the build selected and catches regressions from a
+//! submodule bump or a `build.rs` change.
+
+use std::cmp::Ordering::{self, Equal, Greater, Less};
+
+use libversion_sys::{VersionFlags, compare, compare_with_flags};
+
+const NONE: VersionFlags = VersionFlags::empty();
+const P_IS_PATCH: VersionFlags = VersionFlags::P_IS_PATCH;
+const ANY_IS_PATCH: VersionFlags = VersionFlags::ANY_IS_PATCH;
+const LOWER_BOUND: VersionFlags = VersionFlags::LOWER_BOUND;
+const UPPER_BOUND: VersionFlags = VersionFlags::UPPER_BOUND;
+
+fn check(cases: &[(&str, Ordering, &str)]) {
+    for &(v1, expected, v2) in cases {
+        assert_eq!(compare(v1, v2), expected, "compare({v1:?}, {v2:?})");
+        assert_eq!(
+            compare(v2, v1),
+            expected.reverse(),
+            "compare({v2:?}, {v1:?})"
+        );
+    }
+}
+
+fn check_flags(cases: &[(&str, VersionFlags, Ordering, &str, VersionFlags)]) {
+    for &(v1, f1, expected, v2, f2) in cases {
+        assert_eq!(
+            compare_with_flags(v1, v2, f1, f2),
+            expected,
+            "compare_with_flags({v1:?}, {v2:?}, {f1:?}, {f2:?})"
+        );
+        assert_eq!(
+            compare_with_flags(v2, v1, f2, f1),
+            expected.reverse(),
+            "compare_with_flags({v2:?}, {v1:?}, {f2:?}, {f1:?})"
+        );
+    }
+}
+
+#[test]
+fn equality() {
+    check(&[
+        ("0", Equal, "0"),
+        ("0a", Equal, "0a"),
+        ("a", Equal, "a"),
+        ("a0", Equal, "a0"),
+        ("0a1", Equal, "0a1"),
+        ("0a1b2", Equal, "0a1b2"),
+        ("1alpha1", Equal, "1alpha1"),
+        ("foo", Equal, "foo"),
+        ("1.2.3", Equal, "1.2.3"),
+        ("hello.1", Equal, "hello.1"),
+    ]);
+}
+
+#[test]
+fn different_number_of_components() {
+    check(&[
+        ("1", Equal, "1.0"),
+        ("1", Equal, "1.0.0"),
+        ("1.0", Equal, "1.0.0"),
+        ("1.0", Equal, "1.0.0.0.0.0.0.0"),
+        ("1.0", Less, "1.0.1"),
+        ("1", Less, "1.0.0.1"),
+        ("1.1", Greater, "1.0.99"),
+    ]);
+}
+
+#[test]
+fn leading_zeroes() {
+    check(&[
+        ("00100.00100", Equal, "100.100"),
+        ("0", Equal, "00000000"),
+        ("1.01", Equal, "1.1"),
+        ("1.001", Less, "1.2"),
+        ("1.010", Greater, "1.9"),
+    ]);
+}
+
+#[test]
+fn simple_comparisons() {
+    check(&[
+        ("0.0.0", Less, "0.0.1"),
+        ("0.0.1", Less, "0.0.2"),
+        ("0.0.2", Less, "0.0.10"),
+        ("0.0.2", Less, "0.1.0"),
+        ("0.0.10", Less, "0.1.0"),
+        ("0.1.0", Less, "0.1.1"),
+        ("0.1.1", Less, "1.0.0"),
+        ("1.0.0", Less, "10.0.0"),
+        ("10.0.0", Less, "100.0.0"),
+        ("10.10000.10000", Less, "11.0.0"),
+        ("0.99", Less, "1.11"),
+    ]);
+}
+
+#[test]
+fn long_numbers() {
+    check(&[
+        ("20150101", Less, "20150102"),
+        ("20150101.1", Less, "20150101.2"),
+        ("999999999999999999", Less, "1000000000000000000"),
+        ("18446744073709551615", Less, "18446744073709551616"),
+        (
+            "99999999999999999999999999999999",
+            Less,
+            "100000000000000000000000000000000",
+        ),
+        (
+            "123456789012345678901234567890",
+            Equal,
+            "000123456789012345678901234567890",
+        ),
+    ]);
+}
+
+#[test]
+fn letter_suffixes() {
+    // A letter directly after a number, not followed by a number, is a
+    // letter suffix: it sorts after the plain number, and even after a
+    // further numeric component at the same position.
+    check(&[
+        ("1.0", Less, "1.0a"),
+        ("1.0a", Less, "1.0b"),
+        ("1.0b", Less, "1.1"),
+        ("1.0a", Greater, "1.0.1"),
+        ("1.0z", Greater, "1.0.1"),
+        ("1.0a", Equal, "1.0A"),
+        ("1.0a.1", Greater, "1.0a"),
+        ("1.0a", Greater, "1.0alpha"),
+        ("1a", Greater, "1"),
+        ("1a", Greater, "1.1"),
+        ("1a", Less, "2"),
+    ]);
+}
+
+#[test]
+fn letters_versus_numbers() {
+    check(&[
+        ("a", Less, "0"),
+        ("1.a", Less, "1.0"),
+        ("1.0.a", Less, "1.0"),
+        ("1.a", Less, "1.1"),
+        ("a.1", Less, "1"),
+        ("1a1", Less, "1"),
+        ("1a1", Less, "1.0.1"),
+    ]);
+}
+
+#[test]
+fn pre_release_keywords() {
+    check(&[
+        ("1.0alpha1", Less, "1.0"),
+        ("1.0beta1", Less, "1.0"),
+        ("1.0rc1", Less, "1.0"),
+        ("1.0pre1", Less, "1.0"),
+        ("1.0preview1", Less, "1.0"),
+        ("1.0alpha1", Less, "1.0beta1"),
+        ("1.0beta1", Less, "1.0rc1"),
+        ("1.0alpha1", Less, "1.0alpha2"),
+        ("1.0alpha2", Less, "1.0beta1"),
+        ("1.0rc1", Less, "1.0rc2"),
+        ("1.0alpha", Less, "1.0"),
+        ("1.0.alpha", Less, "1.0"),
+        ("1.0-alpha-1", Less, "1.0"),
+        ("1.0rc1", Greater, "0.9"),
+        ("1.0rc1", Greater, "0.9.99"),
+    ]);
+}
+
+#[test]
+fn keywords_compare_by_first_letter() {
+    check(&[
+        ("1.0alpha1", Equal, "1.0a1"),
+        ("1.0beta1", Equal, "1.0b1"),
+        ("1.0alpha1", Equal, "1.0alfa1"),
+        ("1.0rc1", Equal, "1.0r1"),
+        ("1.0pre1", Equal, "1.0p1"),
+        ("1.0alpha1", Equal, "1.0ALPHA1"),
+        ("1.0Beta1", Equal, "1.0bEtA1"),
+    ]);
+}
+
+#[test]
+fn unknown_letters_are_pre_release() {
+    check(&[
+        ("1.0snapshot1", Less, "1.0"),
+        ("1.0dev1", Less, "1.0"),
+        ("1.0.x", Less, "1.0"),
+        ("1.0dev1", Less, "1.0rc1"),
+        ("1.0x1", Greater, "1.0rc1"),
+    ]);
+}
+
+#[test]
+fn post_release_keywords() {
+    check(&[
+        ("1.0patch1", Greater, "1.0"),
+        ("1.0post1", Greater, "1.0"),
+        ("1.0pl1", Greater, "1.0"),
+        ("1.0errata1", Greater, "1.0"),
+        ("1.0patchlevel1", Greater, "1.0"),
+        ("1.0postfix1", Greater, "1.0"),
+        ("1.0.patch1", Greater, "1.0"),
+        ("1.0patch1", Less, "1.0.1"),
+        ("1.0patch1", Less, "1.0a"),
+        ("1.0patch1", Less, "1.0patch2"),
+        ("1.0patch1", Equal, "1.0post1"),
+        ("1.0patch1", Equal, "1.0pl1"),
+        ("1.0patch1", Greater, "1.0rc1"),
+        ("1.0patch", Greater, "1.0"),
+        ("1.0.PATCH1", Equal, "1.0.patch1"),
+    ]);
+}
+
+#[test]
+fn p_keyword() {
+    // "p" is pre-release unless P_IS_PATCH is set.
+    check(&[
+        ("1.0p1", Less, "1.0"),
+        ("1.0p1", Equal, "1.0pre1"),
+        ("1.0p1", Less, "1.0rc1"),
+    ]);
+    check_flags(&[
+        ("1.0p1", P_IS_PATCH, Greater, "1.0", NONE),
+        ("1.0p1", P_IS_PATCH, Equal, "1.0patch1", NONE),
+        ("1.0p1", P_IS_PATCH, Equal, "1.0post1", NONE),
+        ("1.0P1", P_IS_PATCH, Equal, "1.0post1", NONE),
+        ("1.0p1", P_IS_PATCH, Less, "1.0.1", NONE),
+        ("1.0p1", P_IS_PATCH, Greater, "1.0p1", NONE),
+        ("1.0p", P_IS_PATCH, Greater, "1.0", NONE),
+        ("1.0pre1", P_IS_PATCH, Less, "1.0", NONE),
+        ("1.0p1", P_IS_PATCH, Equal, "1.0p1", P_IS_PATCH),
+        ("1.0p1", P_IS_PATCH, Less, "1.0p2", P_IS_PATCH),
+    ]);
+}
+
+#[test]
+fn any_is_patch() {
+    check_flags(&[
+        ("1.0a1", ANY_IS_PATCH, Greater, "1.0", NONE),
+        ("1.0x1", ANY_IS_PATCH, Greater, "1.0", NONE),
+        ("1.0snapshot1", ANY_IS_PATCH, Greater, "1.0", NONE),
+        ("1.0a1", ANY_IS_PATCH, Less, "1.0.1", NONE),
+        ("1.0p1", ANY_IS_PATCH, Equal, "1.0patch1", NONE),
+        ("1.0x1", ANY_IS_PATCH, Greater, "1.0patch1", NONE),
+        ("1.0x1", ANY_IS_PATCH, Greater, "1.0a1", ANY_IS_PATCH),
+        ("1.0p1", ANY_IS_PATCH, Greater, "1.0", NONE),
+        // Known pre-release keywords are not affected.
+        ("1.0alpha1", ANY_IS_PATCH, Less, "1.0", NONE),
+        ("1.0rc1", ANY_IS_PATCH, Equal, "1.0rc1", NONE),
+        ("1.0pre1", ANY_IS_PATCH, Less, "1.0", NONE),
+        // Letter suffixes are not affected either.
+        ("1.0a", ANY_IS_PATCH, Equal, "1.0a", NONE),
+        ("1.0a", ANY_IS_PATCH, Greater, "1.0.1", NONE),
+    ]);
+}
+
+#[test]
+fn lower_bound() {
+    check_flags(&[
+        ("1.0", LOWER_BOUND, Less, "1.0", NONE),
+        ("1.0", LOWER_BOUND, Less, "1.0.0", NONE),
+        ("1.0", LOWER_BOUND, Less, "1.0alpha1", NONE),
+        ("1.0", LOWER_BOUND, Less, "1.0.alpha1", NONE),
+        ("1.0", LOWER_BOUND, Less, "1.0a", NONE),
+        ("1.0", LOWER_BOUND, Less, "1.0patch1", NONE),
+        ("1.0", LOWER_BOUND, Greater, "0.99", NONE),
+        ("1.0", LOWER_BOUND, Equal, "1.0", LOWER_BOUND),
+        ("1", LOWER_BOUND, Less, "1.0", LOWER_BOUND),
+        ("1.0", LOWER_BOUND, Less, "1.0pre1", LOWER_BOUND),
+        ("1.0", LOWER_BOUND, Less, "1.0", UPPER_BOUND),
+        ("1.0", LOWER_BOUND, Greater, "0.99", UPPER_BOUND),
+    ]);
+}
+
+#[test]
+fn upper_bound() {
+    check_flags(&[
+        ("1.0", UPPER_BOUND, Greater, "1.0", NONE),
+        ("1.0", UPPER_BOUND, Greater, "1.0.0", NONE),
+        ("1.0", UPPER_BOUND, Greater, "1.0.1", NONE),
+        ("1.0", UPPER_BOUND, Greater, "1.0.99999", NONE),
+        ("1.0", UPPER_BOUND, Greater, "1.0a", NONE),
+        ("1.0", UPPER_BOUND, Greater, "1.0patch1", NONE),
+        ("1.0", UPPER_BOUND, Less, "1.1", NONE),
+        ("1.0", UPPER_BOUND, Less, "1.1alpha1", NONE),
+        ("1.0", UPPER_BOUND, Greater, "1.0.1", UPPER_BOUND),
+        ("1.0", UPPER_BOUND, Equal, "1.0", UPPER_BOUND),
+        ("1", UPPER_BOUND, Greater, "1.0", UPPER_BOUND),
+    ]);
+}
+
+#[test]
+fn bound_flags_combined_with_keywords() {
+    check_flags(&[
+        ("1.0p1", LOWER_BOUND | P_IS_PATCH, Greater, "1.0", NONE),
+        ("1.0p1", LOWER_BOUND | P_IS_PATCH, Less, "1.0p1", P_IS_PATCH),
+        (
+            "1.0p1",
+            UPPER_BOUND | P_IS_PATCH,
+            Greater,
+            "1.0p1.1",
+            P_IS_PATCH,
+        ),
+        (
+            "1.0a1",
+            UPPER_BOUND | ANY_IS_PATCH,
+            Greater,
+            "1.0a1",
+            ANY_IS_PATCH,
+        ),
+        // With both bound flags set, LOWER_BOUND wins.
+        ("1.0", LOWER_BOUND | UPPER_BOUND, Equal, "1.0", LOWER_BOUND),
+    ]);
+}
+
+#[test]
+fn separators() {
+    check(&[
+        ("1.0", Equal, "1_0"),
+        ("1.0", Equal, "1-0"),
+        ("1.0", Equal, "1+0"),
+        ("1.0", Equal, "1~0"),
+        ("1.0", Equal, "1 0"),
+        ("1.0", Equal, "1/0"),
+        ("1.0", Equal, "1:0"),
+        ("1.0", Equal, "1..0"),
+        ("1.0", Equal, "1._-+~0"),
+        ("1.0", Equal, "1é0"),
+        ("1.0", Equal, ".1.0."),
+        ("1.0", Equal, "-1-0-"),
+        ("1.0alpha1", Equal, "1.0.alpha.1"),
+        ("1.0alpha1", Equal, "1.0-alpha-1"),
+        ("1.0alpha1", Equal, "1.0_alpha_1"),
+        ("1.0patch1", Equal, "1.0.patch.1"),
+        ("1.0a1", Equal, "1.0-a-1"),
+    ]);
+}
+
+#[test]
+fn separator_changes_letter_meaning() {
+    // A letter is only a suffix when glued to a number and not followed by
+    // one; otherwise it is a pre-release keyword.
+    check(&[
+        ("1.0a", Greater, "1.0.a"),
+        ("1.0a", Greater, "1.0-a"),
+        ("1.0a", Greater, "1.0a1"),
+        ("1.0a.1", Greater, "1.0a1"),
+        ("1.0a-1", Greater, "1.0.a.1"),
+    ]);
+}
+
+#[test]
+fn empty_and_degenerate_strings() {
+    check(&[
+        ("", Equal, ""),
+        ("", Equal, "0"),
+        ("", Equal, "..."),
+        ("", Equal, "0.0.0"),
+        ("", Less, "1"),
+        ("", Greater, "a"),
+        ("", Greater, "alpha"),
+        ("", Less, "patch"),
+        ("-", Equal, "_"),
+    ]);
+}
+
+#[test]
+fn real_world_versions() {
+    check(&[
+        ("1.2.3-rc1", Less, "1.2.3"),
+        ("2.6.32-rc8", Less, "2.6.32"),
+        ("9.6p1", Less, "9.6"),
+        ("1.1.1w", Greater, "1.1.1"),
+        ("1.1.1w", Less, "3.0.0"),
+        ("3.0.0-beta2", Less, "3.0.0"),
+        ("3.0.0-alpha17", Less, "3.0.0-beta1"),
+        ("0.9.8m", Greater, "0.9.8l"),
+        // Only the first letter of a suffix counts.
+        ("0.9.8zh", Equal, "0.9.8za"),
+        ("20230101", Greater, "1.0"),
+        ("1.0.0+git20230101", Less, "1.0.0"),
+        ("1.0~rc1", Less, "1.0"),
+        ("v1.0", Less, "1.0"),
+        ("r1234", Less, "1"),
+        ("2.38.1-1ubuntu1", Greater, "2.38.1"),
+        ("5.15.0-91-generic", Greater, "5.15.0"),
+    ]);
+}
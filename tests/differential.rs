@@ -0,0 +1,292 @@
+//! Differential tests: the C library, the Rust port in `pure`, `explain`,
+//! `SortKey`, `encode_sort_key`, `normalize` and `classify` must agree on every input
+//! and every flag combination.
+//!
+//! Inputs are generated from a fixed seed, so failures are reproducible. Any
+//! disagreement is shrunk to a minimal counterexample before being reported.
+
+#![cfg(feature = "bundled")]
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::os::raw::{c_char, c_int};
+
+use libversion_sys::{
+    Classification, SortKey, Version, VersionFlags, classify, encode_sort_key, explain, ffi,
+    normalize, pure,
+};
+
+const SEED: u64 = 0x5eed_1ab5_e5ee_d5ed;
+const RANDOM_CASES: usize = 3000;
+
+/// Tokens that exercise every branch of libversion's parser.
+#[rustfmt::skip]
+const TOKENS: &[&str] = &[
+    // Numbers, with leading zeroes and beyond 64 bits.
+    "0", "00", "1", "01", "2", "9", "10", "010", "99999999999999999999",
+    // Letters and keywords, in both cases.
+    "a", "b", "x", "z", "A", "Z", "p", "P", "pl", "alpha", "ALPHA", "beta", "rc", "RC",
+    "pre", "preview", "post", "postfix", "patch", "patchlevel", "errata", "snapshot", "dev",
+    // Separators, including non-ASCII and a null byte.
+    ".", "..", "-", "_", "+", "~", " ", "/", "é", "\0",
+];
+
+/// Small xorshift generator, so the suite has no dependencies and runs
+/// offline.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn version(&mut self) -> String {
+        let len = self.below(8);
+        let mut version = String::new();
+        for _ in 0..len {
+            if self.below(8) == 0 {
+                // Arbitrary printable ASCII between tokens.
+                version.push(char::from(b' ' + self.below(95) as u8));
+            } else {
+                version.push_str(TOKENS[self.below(TOKENS.len())]);
+            }
+        }
+        version
+    }
+}
+
+fn all_flags() -> impl Iterator<Item = VersionFlags> {
+    (0..=VersionFlags::all().bits()).map(VersionFlags::from_bits_truncate)
+}
+
+fn c_compare(v1: &str, v2: &str, v1_flags: VersionFlags, v2_flags: VersionFlags) -> Ordering {
+    // Terminate by hand rather than through CString, so that interior null
+    // bytes reach the C parser too.
+    let v1 = [v1.as_bytes(), b"\0"].concat();
+    let v2 = [v2.as_bytes(), b"\0"].concat();
+    let result = unsafe {
+        ffi::version_compare4(
+            v1.as_ptr() as *const c_char,
+            v2.as_ptr() as *const c_char,
+            v1_flags.bits() as c_int,
+            v2_flags.bits() as c_int,
+        )
+    };
+    result.cmp(&0)
+}
+
+fn hash(version: &Version) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    version.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Describe how the Rust side disagrees with C on this input, if it does.
+fn mismatch(v1: &str, v2: &str, v1_flags: VersionFlags, v2_flags: VersionFlags) -> Option<String> {
+    let expected = c_compare(v1, v2, v1_flags, v2_flags);
+    let actual = pure::compare_with_flags(v1, v2, v1_flags, v2_flags);
+    if expected != actual {
+        return Some(format!("C returned {expected:?}, pure returned {actual:?}"));
+    }
+    let explained = explain(v1, v2, v1_flags, v2_flags).ordering();
+    if expected != explained {
+        return Some(format!(
+            "C returned {expected:?}, explain returned {explained:?}"
+        ));
+    }
+    let keys = SortKey::new(v1, v1_flags).cmp(&SortKey::new(v2, v2_flags));
+    if expected != keys {
+        return Some(format!(
+            "C returned {expected:?}, SortKey returned {keys:?}"
+        ));
+    }
+    let encoded = encode_sort_key(v1, v1_flags).cmp(&encode_sort_key(v2, v2_flags));
+    if expected != encoded {
+        return Some(format!(
+            "C returned {expected:?}, encode_sort_key returned {encoded:?}"
+        ));
+    }
+
+    // `normalize` must keep each version equal to itself according to C,
+    // and give versions C considers equal the same spelling.
+    let normalized = normalize(v1, v1_flags);
+    if c_compare(v1, &normalized, v1_flags, v1_flags) != Ordering::Equal {
+        return Some(format!(
+            "C considers {v1:?} and its normalization {normalized:?} unequal"
+        ));
+    }
+    if expected == Ordering::Equal && v1_flags == v2_flags && normalize(v2, v2_flags) != normalized
+    {
+        return Some("C considers them equal, but they normalize differently".to_owned());
+    }
+
+    // `classify` must agree with C: a pre-release sorts before the version
+    // cut off right before its keyword, a post-release after it. The cut
+    // version is a plain version, not a bound.
+    let (keyword, order) = match classify(v1, v1_flags) {
+        Classification::Release => ("", Ordering::Equal),
+        Classification::PreRelease { keyword } => (keyword, Ordering::Less),
+        Classification::PostRelease { keyword } => (keyword, Ordering::Greater),
+    };
+    if order != Ordering::Equal {
+        let release = &v1[..keyword.as_ptr() as usize - v1.as_ptr() as usize];
+        let release_flags = v1_flags - VersionFlags::LOWER_BOUND - VersionFlags::UPPER_BOUND;
+        let actual = c_compare(v1, release, v1_flags, release_flags);
+        if actual != order {
+            return Some(format!(
+                "{v1:?} is classified as {:?}, but C orders it {actual:?} against {release:?}",
+                classify(v1, v1_flags)
+            ));
+        }
+    }
+
+    // `Version` hashes through the Rust parser; C equality must imply equal
+    // hashes. Versions with a null byte can't be compared as `Version`.
+    if expected == Ordering::Equal && !v1.contains('\0') && !v2.contains('\0') {
+        let h1 = hash(&Version::with_flags(v1, v1_flags));
+        let h2 = hash(&Version::with_flags(v2, v2_flags));
+        if h1 != h2 {
+            return Some("C considers them equal, but their Version hashes differ".to_owned());
+        }
+    }
+
+    None
+}
+
+/// Candidate simplifications of `version`: each character removed in turn.
+fn shrink_version(version: &str) -> Vec<String> {
+    version
+        .char_indices()
+        .map(|(i, c)| {
+            let mut shorter = version.to_owned();
+            shorter.replace_range(i..i + c.len_utf8(), "");
+            shorter
+        })
+        .collect()
+}
+
+/// Candidate simplifications of `flags`: each set flag cleared in turn.
+fn shrink_flags(flags: VersionFlags) -> impl Iterator<Item = VersionFlags> {
+    flags.iter().map(move |flag| flags - flag)
+}
+
+/// Greedily simplify a failing case for as long as it keeps failing.
+fn minimize(
+    mut v1: String,
+    mut v2: String,
+    mut v1_flags: VersionFlags,
+    mut v2_flags: VersionFlags,
+) -> (String, String, VersionFlags, VersionFlags) {
+    loop {
+        let fails = |a: &str, b: &str, fa, fb| mismatch(a, b, fa, fb).is_some();
+
+        if let Some(s) = shrink_version(&v1)
+            .into_iter()
+            .find(|s| fails(s, &v2, v1_flags, v2_flags))
+        {
+            v1 = s;
+        } else if let Some(s) = shrink_version(&v2)
+            .into_iter()
+            .find(|s| fails(&v1, s, v1_flags, v2_flags))
+        {
+            v2 = s;
+        } else if let Some(f) = shrink_flags(v1_flags).find(|&f| fails(&v1, &v2, f, v2_flags)) {
+            v1_flags = f;
+        } else if let Some(f) = shrink_flags(v2_flags).find(|&f| fails(&v1, &v2, v1_flags, f)) {
+            v2_flags = f;
+        } else {
+            return (v1, v2, v1_flags, v2_flags);
+        }
+    }
+}
+
+fn check(v1: &str, v2: &str) {
+    for v1_flags in all_flags() {
+        for v2_flags in all_flags() {
+            if mismatch(v1, v2, v1_flags, v2_flags).is_some() {
+                let (v1, v2, v1_flags, v2_flags) =
+                    minimize(v1.to_owned(), v2.to_owned(), v1_flags, v2_flags);
+                panic!(
+                    "implementations disagree on compare({v1:?}, {v2:?}, {v1_flags:?}, {v2_flags:?}): {}",
+                    mismatch(&v1, &v2, v1_flags, v2_flags).unwrap(),
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn adversarial_token_pairs() {
+    // Every pair of single tokens, each glued to a leading number so that
+    // letter suffixes and keyword-after-number paths are covered.
+    for t1 in TOKENS {
+        for t2 in TOKENS {
+            check(t1, t2);
+            check(&format!("1{t1}"), &format!("1{t2}"));
+            check(&format!("1.0{t1}1"), &format!("1.0{t2}"));
+        }
+    }
+}
+
+#[test]
+fn edge_cases() {
+    let long_number = "9".repeat(300);
+    let many_components = ".0".repeat(300);
+    let cases = [
+        ("", ""),
+        ("", "0"),
+        ("", "a"),
+        ("...", "0.0"),
+        ("1.0", "1.0.0"),
+        ("1.0a", "1.0"),
+        ("1.0a1", "1.0"),
+        ("1.0a.1", "1.0a1"),
+        ("1a1b", "1ab"),
+        ("1.0p1", "1.0post1"),
+        ("1.0\x001", "1.0"),
+        ("\0", ""),
+        (long_number.as_str(), "1"),
+        (many_components.as_str(), "0"),
+    ];
+    for (v1, v2) in cases {
+        check(v1, v2);
+        check(v2, v1);
+    }
+}
+
+#[test]
+fn randomized() {
+    let mut rng = Rng(SEED);
+    for _ in 0..RANDOM_CASES {
+        let v1 = rng.version();
+        // Bias towards shared prefixes, which is where the interesting
+        // comparisons happen.
+        let v2 = if rng.below(2) == 0 {
+            let cut = (0..=v1.len() / 2)
+                .rev()
+                .find(|&i| v1.is_char_boundary(i))
+                .unwrap();
+            format!("{}{}", &v1[..cut], rng.version())
+        } else {
+            rng.version()
+        };
+        check(&v1, &v2);
+    }
+}
+
+#[test]
+fn shrinkers() {
+    assert_eq!(shrink_version("1.é"), [".é", "1é", "1."]);
+    assert_eq!(
+        shrink_flags(VersionFlags::P_IS_PATCH | VersionFlags::LOWER_BOUND).collect::<Vec<_>>(),
+        [VersionFlags::LOWER_BOUND, VersionFlags::P_IS_PATCH],
+    );
+}
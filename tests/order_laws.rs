@@ -0,0 +1,186 @@
+//! Property tests proving that `compare` and `compare_with_flags` are total
+//! orders, which `sort_by` relies on.
+//!
+//! For `compare_with_flags` the ordered values are `(version, flags)` pairs.
+//! proptest records any failing case under `proptest-regressions/`; once
+//! understood, add it to `recorded_regressions` as well.
+
+use std::cmp::Ordering;
+
+use libversion_sys::{VersionFlags, compare, compare_with_flags};
+use proptest::prelude::*;
+
+/// A single component as it appears in real version strings.
+fn component() -> impl Strategy<Value = String> {
+    prop_oneof![
+        4 => "[0-9]{1,3}",
+        1 => "0{1,3}[0-9]{0,2}",
+        1 => "[0-9]{15,25}",
+        1 => prop::sample::select(vec![
+            "alpha", "beta", "rc", "pre", "post", "patch", "pl", "errata", "p", "dev",
+            "snapshot", "git", "a", "b", "z", "ALPHA", "RC", "Patch",
+        ])
+        .prop_map(str::to_owned),
+        1 => "[a-zA-Z]{1,6}",
+    ]
+}
+
+fn separator() -> impl Strategy<Value = &'static str> {
+    prop_oneof![
+        6 => Just("."),
+        1 => Just("-"),
+        1 => Just("_"),
+        1 => Just("+"),
+        1 => Just("~"),
+        2 => Just(""),
+    ]
+}
+
+/// Version strings built from realistic components and separators, with an
+/// occasional leading `v` or trailing separator.
+fn version() -> impl Strategy<Value = String> {
+    (
+        prop::option::weighted(0.1, Just("v")),
+        component(),
+        prop::collection::vec((separator(), component()), 0..6),
+        prop::option::weighted(0.1, separator()),
+    )
+        .prop_map(|(prefix, first, rest, suffix)| {
+            let mut version = prefix.unwrap_or_default().to_owned();
+            version.push_str(&first);
+            for (separator, component) in rest {
+                version.push_str(separator);
+                version.push_str(&component);
+            }
+            version.push_str(suffix.unwrap_or_default());
+            version
+        })
+}
+
+fn flags() -> impl Strategy<Value = VersionFlags> {
+    (0..=VersionFlags::all().bits()).prop_map(VersionFlags::from_bits_truncate)
+}
+
+fn flagged_version() -> impl Strategy<Value = (String, VersionFlags)> {
+    (version(), flags())
+}
+
+fn cmp((v1, f1): &(String, VersionFlags), (v2, f2): &(String, VersionFlags)) -> Ordering {
+    compare_with_flags(v1, v2, *f1, *f2)
+}
+
+fn assert_transitive<T>(a: &T, b: &T, c: &T, cmp: impl Fn(&T, &T) -> Ordering) {
+    let (ab, bc, ac) = (cmp(a, b), cmp(b, c), cmp(a, c));
+    if ab == bc {
+        assert_eq!(ac, ab);
+    } else if ab == Ordering::Equal {
+        assert_eq!(ac, bc);
+    } else if bc == Ordering::Equal {
+        assert_eq!(ac, ab);
+    }
+}
+
+/// Sorting and then checking every pair catches intransitive cycles that
+/// independent random triples would almost never hit.
+fn assert_sorted_consistently<T>(mut values: Vec<T>, cmp: impl Fn(&T, &T) -> Ordering) {
+    values.sort_by(&cmp);
+    for (i, a) in values.iter().enumerate() {
+        for b in &values[i + 1..] {
+            assert_ne!(cmp(a, b), Ordering::Greater);
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn compare_is_reflexive(v in version()) {
+        prop_assert_eq!(compare(&v, &v), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_is_antisymmetric(v1 in version(), v2 in version()) {
+        prop_assert_eq!(compare(&v1, &v2), compare(&v2, &v1).reverse());
+    }
+
+    #[test]
+    fn compare_is_transitive(a in version(), b in version(), c in version()) {
+        assert_transitive(&a, &b, &c, |x, y| compare(x, y));
+    }
+
+    #[test]
+    fn compare_sorts_consistently(versions in prop::collection::vec(version(), 0..40)) {
+        assert_sorted_consistently(versions, |x, y| compare(x, y));
+    }
+
+    #[test]
+    fn compare_with_flags_is_reflexive(v in flagged_version()) {
+        prop_assert_eq!(cmp(&v, &v), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_with_flags_is_antisymmetric(v1 in flagged_version(), v2 in flagged_version()) {
+        prop_assert_eq!(cmp(&v1, &v2), cmp(&v2, &v1).reverse());
+    }
+
+    #[test]
+    fn compare_with_flags_is_transitive(
+        a in flagged_version(),
+        b in flagged_version(),
+        c in flagged_version(),
+    ) {
+        assert_transitive(&a, &b, &c, cmp);
+    }
+
+    #[test]
+    fn compare_with_flags_sorts_consistently(
+        versions in prop::collection::vec(flagged_version(), 0..40),
+    ) {
+        assert_sorted_consistently(versions, cmp);
+    }
+}
+
+/// Cases worth keeping regardless of what proptest happens to generate:
+/// values sitting right at metaorder boundaries, where a wrong rank would
+/// break transitivity.
+#[test]
+fn recorded_regressions() {
+    let none = VersionFlags::empty();
+    let triples = [
+        (("1.0", none), ("1.0a", none), ("1.0.1", none)),
+        (("1.0alpha", none), ("1.0", none), ("1.0patch1", none)),
+        (
+            ("1.0p1", none),
+            ("1.0", VersionFlags::P_IS_PATCH),
+            ("1.0p1", VersionFlags::P_IS_PATCH),
+        ),
+        (
+            ("1.0", VersionFlags::LOWER_BOUND),
+            ("1.0alpha1", none),
+            ("1.0", VersionFlags::UPPER_BOUND),
+        ),
+        (
+            ("1", VersionFlags::LOWER_BOUND),
+            ("1.0", VersionFlags::LOWER_BOUND),
+            ("1", none),
+        ),
+        (("", none), ("a", VersionFlags::ANY_IS_PATCH), ("1", none)),
+    ];
+    for (a, b, c) in triples {
+        let own = |(v, f): (&str, VersionFlags)| (v.to_owned(), f);
+        let (a, b, c) = (own(a), own(b), own(c));
+        for (x, y, z) in [
+            (&a, &b, &c),
+            (&a, &c, &b),
+            (&b, &a, &c),
+            (&b, &c, &a),
+            (&c, &a, &b),
+            (&c, &b, &a),
+        ] {
+            assert_transitive(x, y, z, cmp);
+            assert_eq!(cmp(x, y), cmp(y, x).reverse());
+        }
+        assert_sorted_consistently(vec![a, b, c], cmp);
+    }
+}
@@ -0,0 +1,157 @@
+//! Serde round trips for the version types and `VersionFlags`, in a
+//! human-readable format (JSON) and a compact one (`serde_test`'s compact
+//! configuration), checking that spelling and flags survive exactly.
+
+#![cfg(feature = "serde")]
+
+use libversion_sys::{Version, VersionBuf, VersionFlags, VersionStr};
+use serde::{Deserialize, Serialize};
+use serde_test::{Configure, Token, assert_de_tokens_error, assert_tokens};
+
+fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn flags_as_names() {
+    assert_tokens(
+        &(VersionFlags::P_IS_PATCH | VersionFlags::UPPER_BOUND),
+        &[
+            Token::Seq { len: None },
+            Token::Str("P_IS_PATCH"),
+            Token::Str("UPPER_BOUND"),
+            Token::SeqEnd,
+        ],
+    );
+    assert_tokens(
+        &VersionFlags::empty(),
+        &[Token::Seq { len: None }, Token::SeqEnd],
+    );
+    assert_de_tokens_error::<VersionFlags>(
+        &[Token::Seq { len: None }, Token::Str("P_IS_POST")],
+        "unknown variant `P_IS_POST`, expected one of \
+         `P_IS_PATCH`, `ANY_IS_PATCH`, `LOWER_BOUND`, `UPPER_BOUND`",
+    );
+    for bits in 0..=VersionFlags::all().bits() {
+        let flags = VersionFlags::from_bits(bits).unwrap();
+        assert_eq!(round_trip(&flags), flags);
+    }
+    assert!(serde_json::to_string(&VersionFlags::from_bits_retain(0x10)).is_err());
+}
+
+#[test]
+fn version_keeps_spelling_and_flags() {
+    for (spelling, flags) in [
+        ("1.0", VersionFlags::empty()),
+        ("01.00-RC1", VersionFlags::empty()),
+        ("9.6p1", VersionFlags::P_IS_PATCH),
+        (
+            "1.2",
+            VersionFlags::LOWER_BOUND | VersionFlags::ANY_IS_PATCH,
+        ),
+    ] {
+        let version = Version::with_flags(spelling, flags);
+        for back in [
+            round_trip(&version),
+            serde_json::from_value(serde_json::to_value(&version).unwrap()).unwrap(),
+        ] {
+            assert_eq!(back.as_str(), spelling);
+            assert_eq!(back.flags(), flags);
+        }
+    }
+
+    assert_eq!(
+        serde_json::to_string(&Version::new("1.0")).unwrap(),
+        r#""1.0""#
+    );
+    let version: Version = serde_json::from_str(r#"{"version":"1.0p1"}"#).unwrap();
+    assert_eq!(version.flags(), VersionFlags::empty());
+    assert!(serde_json::from_str::<Version>(r#"{"flags":[]}"#).is_err());
+    assert!(serde_json::from_str::<Version>(r#"{"version":"1","tag":"x"}"#).is_err());
+}
+
+#[test]
+fn version_compact() {
+    let version = Version::with_flags("1.0p1", VersionFlags::P_IS_PATCH);
+    assert_tokens(
+        &version.compact(),
+        &[
+            Token::Struct {
+                name: "Version",
+                len: 2,
+            },
+            Token::Str("version"),
+            Token::Str("1.0p1"),
+            Token::Str("flags"),
+            Token::Seq { len: None },
+            Token::Str("P_IS_PATCH"),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+    assert_tokens(&Version::new("2.0").readable(), &[Token::Str("2.0")]);
+}
+
+#[test]
+fn version_str_and_buf() {
+    let buf = VersionBuf::from("1.00");
+    assert_eq!(serde_json::to_string(&buf).unwrap(), r#""1.00""#);
+    assert_eq!(round_trip(&buf).as_str(), "1.00");
+
+    let json = String::from(r#""2.0rc1""#);
+    let borrowed: &VersionStr = serde_json::from_str(&json).unwrap();
+    assert_eq!(borrowed.as_str(), "2.0rc1");
+    assert_eq!(serde_json::to_string(borrowed).unwrap(), json);
+}
+
+#[test]
+fn null_bytes_are_rejected() {
+    let err = serde_json::from_str::<Version>(r#""1.0\u00001""#).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("version contains an interior null byte at position 3"),
+        "{err}",
+    );
+    assert!(serde_json::from_str::<VersionBuf>(r#""\u0000""#).is_err());
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Releases {
+        #[serde(with = "libversion_sys::serde::sorted")]
+        versions: Vec<String>,
+    }
+    assert!(serde_json::from_str::<Releases>(r#"{"versions":["1","1\u0000"]}"#).is_err());
+    let releases = Releases {
+        versions: vec!["1".into(), "1\0".into()],
+    };
+    let err = serde_json::to_string(&releases).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "version contains an interior null byte at position 1"
+    );
+}
+
+#[test]
+fn sorted_helper() {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Releases {
+        #[serde(with = "libversion_sys::serde::sorted")]
+        versions: Vec<String>,
+    }
+
+    let releases: Releases =
+        serde_json::from_str(r#"{"versions":["1.10","1.0.0","1.2","1.0","1.0alpha1"]}"#).unwrap();
+    assert_eq!(
+        releases.versions,
+        ["1.0alpha1", "1.0.0", "1.0", "1.2", "1.10"]
+    );
+
+    let releases = Releases {
+        versions: vec!["2.0".into(), "2.0rc1".into(), "10".into()],
+    };
+    assert_eq!(
+        serde_json::to_string(&releases).unwrap(),
+        r#"{"versions":["2.0rc1","2.0","10"]}"#
+    );
+    // Serializing does not reorder the field itself.
+    assert_eq!(releases.versions, ["2.0", "2.0rc1", "10"]);
+}